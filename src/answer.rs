@@ -0,0 +1,297 @@
+use crate::grid::Grid;
+use num_bigint::BigInt;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+// Answer of a part. Answers are compared through their canonical text, the text submitted to the
+// puzzle: numbers are equal whatever their type, a number equals the text of its digits and a grid
+// of block letters equals the letters it spells
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Signed(i64),
+    Unsigned(u64),
+    Big(BigInt),
+    Text(String),
+    // Rows of a drawing, e.g. block letters, `#` for lit cells and `.` for the others
+    Grid(Vec<String>),
+}
+
+impl Answer {
+    // Any character other than `.` and ` ` is a lit cell, rows are padded to the same width
+    pub fn grid<S: AsRef<str>>(rows: impl IntoIterator<Item = S>) -> Self {
+        let rows: Vec<String> = rows
+            .into_iter()
+            .map(|row| {
+                row.as_ref()
+                    .chars()
+                    .map(|char| if is_lit(char) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect();
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        Answer::Grid(
+            rows.into_iter()
+                .map(|row| format!("{:.<width$}", row, width = width))
+                .collect(),
+        )
+    }
+
+    // Text submitted for the answer, the letters of a grid when they can be read, its rows otherwise
+    pub fn canonical(&self) -> String {
+        match self {
+            Answer::Signed(value) => value.to_string(),
+            Answer::Unsigned(value) => value.to_string(),
+            Answer::Big(value) => value.to_string(),
+            Answer::Text(text) => text.trim().to_string(),
+            Answer::Grid(rows) => ocr(rows).unwrap_or_else(|| rows.join("\n")),
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            // the drawing starts on its own line
+            Answer::Grid(rows) if ocr(rows).is_none() => write!(f, "\n{}", rows.join("\n")),
+            _ => write!(f, "{}", self.canonical()),
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        self.canonical() == other.canonical()
+    }
+}
+
+impl Eq for Answer {}
+
+impl Hash for Answer {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.canonical().hash(state);
+    }
+}
+
+macro_rules! answer_from_int {
+    ($variant:ident, $as:ty, $($int:ty),*) => {$(
+        impl From<$int> for Answer {
+            fn from(value: $int) -> Self {
+                Answer::$variant(value as $as)
+            }
+        }
+    )*};
+}
+
+answer_from_int!(Signed, i64, i8, i16, i32, i64, isize);
+answer_from_int!(Unsigned, u64, u8, u16, u32, u64, usize);
+
+impl From<i128> for Answer {
+    fn from(value: i128) -> Self {
+        i64::try_from(value).map_or_else(|_| Answer::Big(value.into()), Answer::Signed)
+    }
+}
+
+impl From<u128> for Answer {
+    fn from(value: u128) -> Self {
+        u64::try_from(value).map_or_else(|_| Answer::Big(value.into()), Answer::Unsigned)
+    }
+}
+
+impl From<BigInt> for Answer {
+    fn from(value: BigInt) -> Self {
+        Answer::Big(value)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+// Lit cells are `true`
+impl From<&Grid<bool>> for Answer {
+    fn from(grid: &Grid<bool>) -> Self {
+        Answer::grid((0..grid.rows()).map(|row| {
+            grid.row(row)
+                .map(|&lit| if lit { '#' } else { '.' })
+                .collect::<String>()
+        }))
+    }
+}
+
+// How answers are written in JSON. Integers that do not fit 64 bits are written as strings and
+// read back as text, which is equal to them
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum AnswerRepr {
+    Signed(i64),
+    Unsigned(u64),
+    Text(String),
+    Grid { grid: Vec<String> },
+}
+
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Answer::Signed(value) => AnswerRepr::Signed(*value),
+            Answer::Unsigned(value) => AnswerRepr::Unsigned(*value),
+            Answer::Big(value) => AnswerRepr::Text(value.to_string()),
+            Answer::Text(text) => AnswerRepr::Text(text.clone()),
+            Answer::Grid(rows) => AnswerRepr::Grid { grid: rows.clone() },
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Answer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match AnswerRepr::deserialize(deserializer)? {
+            AnswerRepr::Signed(value) => Answer::Signed(value),
+            AnswerRepr::Unsigned(value) => Answer::Unsigned(value),
+            AnswerRepr::Text(text) => Answer::Text(text),
+            AnswerRepr::Grid { grid } => Answer::Grid(grid),
+        })
+    }
+}
+
+fn is_lit(char: char) -> bool {
+    !matches!(char, '.' | ' ')
+}
+
+const LETTER_HEIGHT: usize = 6;
+
+// The 6 rows high block letters drawn by the puzzles
+const LETTERS: [(char, [&str; LETTER_HEIGHT]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+// Reads the block letters of a drawing, `None` when a shape is not a known letter.
+// Letters are told apart by the empty columns between them
+pub fn ocr<S: AsRef<str>>(rows: &[S]) -> Option<String> {
+    let cells: Vec<Vec<bool>> = rows
+        .iter()
+        .map(|row| row.as_ref().chars().map(is_lit).collect())
+        .skip_while(|row: &Vec<bool>| !row.contains(&true))
+        .collect();
+    let height = cells.iter().rposition(|row| row.contains(&true))? + 1;
+    if height != LETTER_HEIGHT {
+        return None;
+    }
+    let width = cells.iter().map(|row| row.len()).max()?;
+    let lit = |row: usize, column: usize| cells[row].get(column).copied().unwrap_or(false);
+    let column_is_empty = |column: usize| (0..height).all(|row| !lit(row, column));
+
+    let mut text = String::new();
+    let mut column = 0;
+    while column < width {
+        if column_is_empty(column) {
+            column += 1;
+            continue;
+        }
+        let end = (column..width)
+            .find(|&end| column_is_empty(end))
+            .unwrap_or(width);
+        let shape: Vec<String> = (0..height)
+            .map(|row| {
+                (column..end)
+                    .map(|c| if lit(row, c) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect();
+        let (letter, _) = LETTERS
+            .iter()
+            .find(|(_, glyph)| glyph.iter().zip(shape.iter()).all(|(a, b)| a == b))?;
+        text.push(*letter);
+        column = end;
+    }
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Draws the letters side by side, one empty column apart
+    fn draw(letters: &[[&str; LETTER_HEIGHT]]) -> Vec<String> {
+        (0..LETTER_HEIGHT)
+            .map(|row| {
+                letters
+                    .iter()
+                    .map(|glyph| glyph[row])
+                    .collect::<Vec<_>>()
+                    .join(".")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ocr_reads_every_letter() {
+        for (letter, glyph) in LETTERS {
+            assert_eq!(ocr(&glyph), Some(letter.to_string()), "letter {}", letter);
+        }
+    }
+
+    #[test]
+    fn ocr_reads_a_word() {
+        let glyphs: Vec<_> = LETTERS.iter().map(|(_, glyph)| *glyph).collect();
+        let expected: String = LETTERS.iter().map(|(letter, _)| letter).collect();
+        assert_eq!(ocr(&draw(&glyphs)), Some(expected));
+    }
+
+    #[test]
+    fn ocr_skips_blank_rows_and_accepts_spaces() {
+        let mut rows = vec!["      ".to_string()];
+        rows.extend(
+            draw(&[LETTERS[0].1])
+                .iter()
+                .map(|row| row.replace('.', " ")),
+        );
+        rows.push(String::new());
+        assert_eq!(ocr(&rows), Some("A".to_string()));
+    }
+
+    #[test]
+    fn ocr_rejects_unknown_glyphs() {
+        // a full block is no letter, even next to known ones
+        let block = ["####"; LETTER_HEIGHT];
+        assert_eq!(ocr(&block), None);
+        assert_eq!(ocr(&draw(&[LETTERS[0].1, block])), None);
+        // the letters are 6 rows high
+        assert_eq!(ocr(&LETTERS[0].1[..5]), None);
+        assert_eq!(ocr::<&str>(&[]), None);
+    }
+
+    #[test]
+    fn grids_equal_the_letters_they_spell() {
+        let grid = Answer::grid(draw(&[LETTERS[6].1, LETTERS[7].1]));
+        assert_eq!(grid, Answer::from("HI"));
+        assert_eq!(grid.to_string(), "HI");
+        let unknown = Answer::grid(["#.#", ".#."]);
+        assert_eq!(unknown.to_string(), "\n#.#\n.#.");
+    }
+}
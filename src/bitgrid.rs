@@ -0,0 +1,342 @@
+use crate::cache::Cacheable;
+use crate::point::{Direction, Point};
+
+// Dense set of grid cells stored as bits, optionally one bit per cell and direction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitGrid {
+    words: Vec<u64>,
+    rows: usize,
+    columns: usize,
+    // Bits per cell, 1 for a set of cells and 8 for a set of (cell, direction)
+    layers: usize,
+}
+
+impl BitGrid {
+    fn with_layers(rows: usize, columns: usize, layers: usize) -> Self {
+        Self {
+            words: vec![0; (rows * columns * layers).div_ceil(64)],
+            rows,
+            columns,
+            layers,
+        }
+    }
+
+    pub fn new(rows: usize, columns: usize) -> Self {
+        Self::with_layers(rows, columns, 1)
+    }
+
+    // Set of (cell, direction) pairs
+    pub fn with_directions(rows: usize, columns: usize) -> Self {
+        Self::with_layers(rows, columns, Direction::ALL.len())
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn in_bounds(&self, point: Point) -> bool {
+        0 <= point.row
+            && point.row < self.rows as i64
+            && 0 <= point.column
+            && point.column < self.columns as i64
+    }
+
+    fn first_bit(&self, point: Point) -> Option<usize> {
+        self.in_bounds(point)
+            .then(|| (point.row as usize * self.columns + point.column as usize) * self.layers)
+    }
+
+    fn expect_bit(&self, point: Point) -> usize {
+        self.first_bit(point).unwrap_or_else(|| {
+            panic!(
+                "point ({}, {}) is outside of the {}x{} grid",
+                point.row, point.column, self.rows, self.columns
+            )
+        })
+    }
+
+    fn get_bit(&self, bit: usize) -> bool {
+        self.words[bit / 64] & (1 << (bit % 64)) != 0
+    }
+
+    // Returns whether the bit was not set yet
+    fn set_bit(&mut self, bit: usize) -> bool {
+        let word = &mut self.words[bit / 64];
+        let mask = 1 << (bit % 64);
+        let inserted = *word & mask == 0;
+        *word |= mask;
+        inserted
+    }
+
+    // Returns whether the cell was not in the set yet, panics outside of the grid
+    pub fn insert(&mut self, point: Point) -> bool {
+        assert_eq!(self.layers, 1, "cells of a directed grid need a direction");
+        let bit = self.expect_bit(point);
+        self.set_bit(bit)
+    }
+
+    // Returns whether the pair was not in the set yet, panics outside of the grid
+    pub fn insert_directed(&mut self, point: Point, direction: Direction) -> bool {
+        assert_eq!(
+            self.layers, 8,
+            "cells of an undirected grid have no direction"
+        );
+        let bit = self.expect_bit(point) + direction as usize;
+        self.set_bit(bit)
+    }
+
+    // Whether the cell is in the set, with any direction for a directed grid
+    pub fn contains(&self, point: Point) -> bool {
+        self.first_bit(point)
+            .is_some_and(|first| (first..first + self.layers).any(|bit| self.get_bit(bit)))
+    }
+
+    pub fn contains_directed(&self, point: Point, direction: Direction) -> bool {
+        assert_eq!(
+            self.layers, 8,
+            "cells of an undirected grid have no direction"
+        );
+        self.first_bit(point)
+            .is_some_and(|first| self.get_bit(first + direction as usize))
+    }
+
+    // Removes the cell with all its directions, returns whether it was in the set
+    pub fn remove(&mut self, point: Point) -> bool {
+        let Some(first) = self.first_bit(point) else {
+            return false;
+        };
+        let mut removed = false;
+        for bit in first..first + self.layers {
+            removed |= self.get_bit(bit);
+            self.words[bit / 64] &= !(1 << (bit % 64));
+        }
+        removed
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    // Number of elements, i.e. of (cell, direction) pairs for a directed grid
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    // Cells in the set, row by row
+    pub fn cells(&self) -> impl Iterator<Item = Point> + '_ {
+        let mut last_cell = None;
+        self.words
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &word)| {
+                let mut word = word;
+                std::iter::from_fn(move || {
+                    if word == 0 {
+                        return None;
+                    }
+                    let bit = i * 64 + word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    Some(bit)
+                })
+            })
+            .filter_map(move |bit| {
+                let cell = bit / self.layers;
+                if last_cell == Some(cell) {
+                    return None;
+                }
+                last_cell = Some(cell);
+                Some(Point::from_position((
+                    cell / self.columns,
+                    cell % self.columns,
+                )))
+            })
+    }
+
+    // Number of cells in the set, ignoring directions
+    pub fn count_cells(&self) -> usize {
+        match self.layers {
+            1 => self.len(),
+            _ => self.cells().count(),
+        }
+    }
+
+    // Set of the cells, dropping directions
+    pub fn cell_set(&self) -> BitGrid {
+        let mut cells = BitGrid::new(self.rows, self.columns);
+        for point in self.cells() {
+            cells.insert(point);
+        }
+        cells
+    }
+
+    fn combine_with<F: Fn(u64, u64) -> u64>(&mut self, other: &BitGrid, combine: F) {
+        assert!(
+            self.rows == other.rows && self.columns == other.columns && self.layers == other.layers,
+            "cannot combine grids of different shapes"
+        );
+        for (word, &other_word) in self.words.iter_mut().zip(other.words.iter()) {
+            *word = combine(*word, other_word);
+        }
+    }
+
+    pub fn union_with(&mut self, other: &BitGrid) {
+        self.combine_with(other, |a, b| a | b);
+    }
+
+    pub fn intersect_with(&mut self, other: &BitGrid) {
+        self.combine_with(other, |a, b| a & b);
+    }
+
+    pub fn difference_with(&mut self, other: &BitGrid) {
+        self.combine_with(other, |a, b| a & !b);
+    }
+}
+
+impl Cacheable for BitGrid {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.rows.write(out);
+        self.columns.write(out);
+        self.layers.write(out);
+        self.words.write(out);
+    }
+
+    fn read(input: &mut &[u8]) -> Option<Self> {
+        let grid = BitGrid {
+            rows: usize::read(input)?,
+            columns: usize::read(input)?,
+            layers: usize::read(input)?,
+            words: Vec::read(input)?,
+        };
+        let bits = grid
+            .rows
+            .checked_mul(grid.columns)?
+            .checked_mul(grid.layers)?;
+        (grid.words.len() == bits.div_ceil(64)).then_some(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid_of(rows: usize, columns: usize, cells: &[(usize, usize)]) -> BitGrid {
+        let mut grid = BitGrid::new(rows, columns);
+        for &cell in cells {
+            grid.insert(Point::from_position(cell));
+        }
+        grid
+    }
+
+    #[test]
+    fn insert_contains_remove() {
+        // 72 cells, spread over two words
+        let mut grid = BitGrid::new(8, 9);
+        assert!(grid.is_empty());
+        assert!(grid.insert(Point::new(7, 8)));
+        assert!(!grid.insert(Point::new(7, 8)));
+        assert!(grid.insert(Point::new(0, 0)));
+        assert!(grid.contains(Point::new(7, 8)));
+        assert!(!grid.contains(Point::new(7, 7)));
+        assert!(!grid.contains(Point::new(-1, 0)));
+        assert!(!grid.contains(Point::new(8, 0)));
+        assert_eq!(grid.len(), 2);
+        assert!(grid.remove(Point::new(7, 8)));
+        assert!(!grid.remove(Point::new(7, 8)));
+        assert!(!grid.remove(Point::new(0, 9)));
+        assert_eq!(grid.len(), 1);
+        grid.clear();
+        assert!(grid.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "outside of the 2x3 grid")]
+    fn insert_outside_panics() {
+        BitGrid::new(2, 3).insert(Point::new(0, 3));
+    }
+
+    #[test]
+    fn directed_cells() {
+        let mut grid = BitGrid::with_directions(3, 5);
+        let point = Point::new(2, 4);
+        assert!(grid.insert_directed(point, Direction::Up));
+        assert!(grid.insert_directed(point, Direction::Left));
+        assert!(!grid.insert_directed(point, Direction::Up));
+        assert!(grid.insert_directed(Point::new(0, 1), Direction::Down));
+        assert!(grid.contains_directed(point, Direction::Left));
+        assert!(!grid.contains_directed(point, Direction::Right));
+        assert!(grid.contains(point));
+        assert_eq!(grid.len(), 3);
+        assert_eq!(grid.count_cells(), 2);
+        assert_eq!(grid.cell_set(), grid_of(3, 5, &[(0, 1), (2, 4)]));
+        // removing a cell drops all its directions
+        assert!(grid.remove(point));
+        assert!(!grid.contains_directed(point, Direction::Up));
+        assert_eq!(grid.len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "need a direction")]
+    fn undirected_insert_into_a_directed_grid_panics() {
+        BitGrid::with_directions(2, 2).insert(Point::new(0, 0));
+    }
+
+    #[test]
+    fn cells_row_by_row() {
+        let grid = grid_of(8, 9, &[(7, 1), (0, 3), (3, 0), (7, 8)]);
+        let cells: Vec<Point> = grid.cells().collect();
+        assert_eq!(
+            cells,
+            [
+                Point::new(0, 3),
+                Point::new(3, 0),
+                Point::new(7, 1),
+                Point::new(7, 8)
+            ]
+        );
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = grid_of(2, 40, &[(0, 0), (0, 1), (1, 39)]);
+        let b = grid_of(2, 40, &[(0, 1), (1, 0)]);
+        let mut union = a.clone();
+        union.union_with(&b);
+        assert_eq!(union, grid_of(2, 40, &[(0, 0), (0, 1), (1, 0), (1, 39)]));
+        let mut intersection = a.clone();
+        intersection.intersect_with(&b);
+        assert_eq!(intersection, grid_of(2, 40, &[(0, 1)]));
+        let mut difference = a;
+        difference.difference_with(&b);
+        assert_eq!(difference, grid_of(2, 40, &[(0, 0), (1, 39)]));
+    }
+
+    #[test]
+    #[should_panic(expected = "different shapes")]
+    fn combining_different_shapes_panics() {
+        BitGrid::new(2, 3).union_with(&BitGrid::new(3, 2));
+    }
+
+    #[test]
+    fn cache_round_trip() {
+        let grid = grid_of(8, 9, &[(1, 2), (7, 8)]);
+        let mut bytes = Vec::new();
+        grid.write(&mut bytes);
+        assert_eq!(BitGrid::read(&mut bytes.as_slice()), Some(grid));
+        // the words do not match the announced shape
+        let mut bytes = Vec::new();
+        BitGrid::new(1, 1).write(&mut bytes);
+        bytes[0] = 100;
+        assert_eq!(BitGrid::read(&mut bytes.as_slice()), None);
+    }
+}
@@ -0,0 +1,223 @@
+use crate::error::{Error, Result};
+use crate::graph::Graph;
+use crate::point::{Direction, Point};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::hash::Hash;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+// Parsed inputs saved in a binary file named after the hash of the input, so the next run can skip parsing.
+// A cached file is only used when it was written from the same parser sources, any change to them
+// (or to the encoding) invalidates it.
+
+pub const DEFAULT_CACHE_DIR: &str = ".cache";
+
+const MAGIC: &[u8; 8] = b"AOCCACHE";
+// Bump when the encoding of the types below changes
+const FORMAT_VERSION: u32 = 1;
+// Shared parsing code every day relies on
+const SHARED_PARSER_SOURCES: [&str; 2] = [include_str!("parse.rs"), include_str!("grid.rs")];
+
+static CACHE_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+// Turns caching on for the rest of the run
+pub fn enable(dir: &Path) {
+    let _ = CACHE_DIR.set(dir.to_path_buf());
+}
+
+pub fn is_enabled() -> bool {
+    CACHE_DIR.get().is_some()
+}
+
+// Directory given to `enable`, `None` while caching is off
+pub fn dir() -> Option<&'static Path> {
+    CACHE_DIR.get().map(|dir| dir.as_path())
+}
+
+// Types that can be written to and read back from a cache file
+pub trait Cacheable: Sized {
+    fn write(&self, out: &mut Vec<u8>);
+
+    // `None` when the bytes are not a valid encoding
+    fn read(input: &mut &[u8]) -> Option<Self>;
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    if input.len() < len {
+        return None;
+    }
+    let (bytes, rest) = input.split_at(len);
+    *input = rest;
+    Some(bytes)
+}
+
+macro_rules! cacheable_int {
+    ($($int:ty),*) => {$(
+        impl Cacheable for $int {
+            fn write(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+
+            fn read(input: &mut &[u8]) -> Option<Self> {
+                let bytes = take(input, std::mem::size_of::<$int>())?;
+                Some(<$int>::from_le_bytes(bytes.try_into().ok()?))
+            }
+        }
+    )*};
+}
+
+cacheable_int!(u8, u32, u64, i32, i64);
+
+impl Cacheable for usize {
+    fn write(&self, out: &mut Vec<u8>) {
+        (*self as u64).write(out);
+    }
+
+    fn read(input: &mut &[u8]) -> Option<Self> {
+        usize::try_from(u64::read(input)?).ok()
+    }
+}
+
+impl<T: Cacheable> Cacheable for Vec<T> {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.len().write(out);
+        for item in self {
+            item.write(out);
+        }
+    }
+
+    fn read(input: &mut &[u8]) -> Option<Self> {
+        let len = usize::read(input)?;
+        // the length comes from the file, do not trust it for the allocation
+        let mut items = Vec::with_capacity(len.min(input.len()));
+        for _ in 0..len {
+            items.push(T::read(input)?);
+        }
+        Some(items)
+    }
+}
+
+impl<A: Cacheable, B: Cacheable> Cacheable for (A, B) {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.0.write(out);
+        self.1.write(out);
+    }
+
+    fn read(input: &mut &[u8]) -> Option<Self> {
+        Some((A::read(input)?, B::read(input)?))
+    }
+}
+
+impl Cacheable for Point {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.row.write(out);
+        self.column.write(out);
+    }
+
+    fn read(input: &mut &[u8]) -> Option<Self> {
+        Some(Point::new(i64::read(input)?, i64::read(input)?))
+    }
+}
+
+impl Cacheable for Direction {
+    fn write(&self, out: &mut Vec<u8>) {
+        (*self as u8).write(out);
+    }
+
+    fn read(input: &mut &[u8]) -> Option<Self> {
+        Direction::ALL.get(u8::read(input)? as usize).copied()
+    }
+}
+
+// Nodes in insertion order followed by the successors of each node
+impl<N: Cacheable + Clone + Eq + Hash> Cacheable for Graph<N> {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.nodes().to_vec().write(out);
+        for node in self.nodes() {
+            self.successors(node)
+                .cloned()
+                .collect::<Vec<N>>()
+                .write(out);
+        }
+    }
+
+    fn read(input: &mut &[u8]) -> Option<Self> {
+        let nodes = Vec::<N>::read(input)?;
+        let mut graph = Graph::new();
+        for node in nodes.iter() {
+            graph.add_node(node.clone());
+        }
+        for node in nodes {
+            for successor in Vec::<N>::read(input)? {
+                graph.add_edge(node.clone(), successor);
+            }
+        }
+        Some(graph)
+    }
+}
+
+// Hash of everything that can change what the parser produces
+fn parser_fingerprint(parser_source: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(FORMAT_VERSION.to_le_bytes());
+    for source in SHARED_PARSER_SOURCES.iter().chain([&parser_source]) {
+        hasher.update((source.len() as u64).to_le_bytes());
+        hasher.update(source.as_bytes());
+    }
+    hasher.finalize().into()
+}
+
+fn cache_file(dir: &Path, key: &str, input: &[u8]) -> PathBuf {
+    let hash: String = Sha256::digest(input)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    dir.join(format!("{}-{}.bin", key, hash))
+}
+
+fn decode<T: Cacheable>(mut bytes: &[u8], fingerprint: &[u8; 32]) -> Option<T> {
+    let input = &mut bytes;
+    if take(input, MAGIC.len())? != MAGIC || take(input, 32)? != fingerprint {
+        return None;
+    }
+    let value = T::read(input)?;
+    input.is_empty().then_some(value)
+}
+
+fn io_error(path: &Path) -> impl FnOnce(std::io::Error) -> Error + '_ {
+    move |source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+// Parses the input with `parse`, going through the cache when it is enabled.
+// `key` names the cached structure, e.g. `day_5`, and `parser_source` is the source of the code
+// producing it, usually `include_str!("mod.rs")`
+pub fn parse_cached<T, F>(file_path: &Path, key: &str, parser_source: &str, parse: F) -> Result<T>
+where
+    T: Cacheable,
+    F: FnOnce(&Path) -> Result<T>,
+{
+    let Some(dir) = CACHE_DIR.get() else {
+        return parse(file_path);
+    };
+    let input = fs::read(file_path).map_err(io_error(file_path))?;
+    let path = cache_file(dir, key, &input);
+    let fingerprint = parser_fingerprint(parser_source);
+    // missing, stale or corrupted cache files are rebuilt
+    if let Some(value) = fs::read(&path)
+        .ok()
+        .and_then(|bytes| decode(&bytes, &fingerprint))
+    {
+        return Ok(value);
+    }
+    let value = parse(file_path)?;
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend_from_slice(&fingerprint);
+    value.write(&mut bytes);
+    fs::create_dir_all(dir).map_err(io_error(dir))?;
+    fs::write(&path, bytes).map_err(io_error(&path))?;
+    Ok(value)
+}
@@ -0,0 +1,178 @@
+use advent_of_code_2024::simulation::Simulation;
+use anyhow::{anyhow, Result};
+use std::io::{self, IsTerminal, Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+pub const DEFAULT_FPS: f64 = 30.0;
+
+const CONTROLS: &str = "space: pause/resume, n: next frame while paused, +/-: speed, q: quit";
+
+pub struct Animator {
+    pub fps: f64,
+    // Steps simulated between two drawn frames, 1 draws every step
+    pub skip: usize,
+    pub color: bool,
+}
+
+enum Key {
+    Pause,
+    Next,
+    Faster,
+    Slower,
+    Quit,
+}
+
+// Puts the terminal in non-canonical mode without echo so keys are read as soon as they are typed,
+// the previous settings are restored when dropped
+struct RawMode {
+    saved: Option<String>,
+}
+
+impl RawMode {
+    fn enable() -> Self {
+        let saved = stty(&["-g"]).filter(|_| stty(&["-icanon", "-echo"]).is_some());
+        Self { saved }
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        if let Some(saved) = &self.saved {
+            stty(&[saved.trim()]);
+        }
+    }
+}
+
+// Hides the cursor while frames are drawn, it is shown again when dropped, whichever way playing ends
+struct HiddenCursor;
+
+impl HiddenCursor {
+    fn hide() -> Self {
+        print!("\x1b[?25l");
+        Self
+    }
+}
+
+impl Drop for HiddenCursor {
+    fn drop(&mut self) {
+        print!("\x1b[?25h");
+        let _ = io::stdout().flush();
+    }
+}
+
+// Frame rates must be positive, the delay between two frames is derived from them
+pub fn check_fps(fps: f64) -> Result<f64> {
+    if fps.is_finite() && fps > 0.0 {
+        Ok(fps)
+    } else {
+        Err(anyhow!(
+            "Invalid value for `--fps`: `{}`, expected a positive number",
+            fps
+        ))
+    }
+}
+
+fn stty(args: &[&str]) -> Option<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+// Keys typed on stdin, read from a background thread
+fn spawn_key_reader() -> Receiver<Key> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut stdin = io::stdin().lock();
+        let mut byte = [0u8];
+        // keys arrive one by one in raw mode, buffering would delay them
+        while let Ok(1) = stdin.read(&mut byte) {
+            let key = match byte[0] {
+                b' ' | b'p' => Key::Pause,
+                b'n' | b'.' => Key::Next,
+                b'+' | b'=' => Key::Faster,
+                b'-' => Key::Slower,
+                b'q' => Key::Quit,
+                _ => continue,
+            };
+            if sender.send(key).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+impl Animator {
+    fn draw(&self, simulation: &dyn Simulation, paused: bool) -> Result<()> {
+        let mut stdout = io::stdout().lock();
+        // move the cursor home and draw over the previous frame
+        write!(stdout, "\x1b[H{}", simulation.frame().render(self.color))?;
+        write!(stdout, "\x1b[K{}", simulation.status())?;
+        if paused {
+            write!(stdout, " [paused]")?;
+        }
+        writeln!(stdout, "\n\x1b[K{} ({:.0} fps)", CONTROLS, self.fps)?;
+        stdout.flush()?;
+        Ok(())
+    }
+
+    // Advances the simulation by up to `skip` steps, returns false once it is over
+    fn advance(&self, simulation: &mut dyn Simulation) -> bool {
+        (0..self.skip.max(1)).all(|_| simulation.step())
+    }
+
+    // Plays the simulation until it is over or `q` is pressed, keyboard controls only work in a terminal
+    pub fn play(mut self, simulation: &mut dyn Simulation) -> Result<()> {
+        check_fps(self.fps)?;
+        let interactive = io::stdin().is_terminal();
+        let _raw_mode = interactive.then(RawMode::enable);
+        let keys = if interactive {
+            spawn_key_reader()
+        } else {
+            mpsc::channel().1
+        };
+        print!("\x1b[2J");
+        let _hidden_cursor = HiddenCursor::hide();
+        let mut paused = false;
+        let mut running = true;
+        while running {
+            self.draw(simulation, paused)?;
+            let key = if paused {
+                keys.recv().ok()
+            } else {
+                match keys.recv_timeout(Duration::from_secs_f64(1.0 / self.fps)) {
+                    Ok(key) => Some(key),
+                    Err(RecvTimeoutError::Timeout) => {
+                        running = self.advance(simulation);
+                        None
+                    }
+                    // no keyboard, keep playing
+                    Err(RecvTimeoutError::Disconnected) => {
+                        thread::sleep(Duration::from_secs_f64(1.0 / self.fps));
+                        running = self.advance(simulation);
+                        None
+                    }
+                }
+            };
+            match key {
+                Some(Key::Pause) => paused = !paused,
+                Some(Key::Next) if paused => running = self.advance(simulation),
+                Some(Key::Next) => {}
+                Some(Key::Faster) => self.fps = (self.fps * 1.5).min(1000.0),
+                Some(Key::Slower) => self.fps = (self.fps / 1.5).max(0.5),
+                Some(Key::Quit) => return Ok(()),
+                None => {}
+            }
+        }
+        self.draw(simulation, false)
+    }
+}
@@ -0,0 +1,136 @@
+use advent_of_code_2024::error;
+use advent_of_code_2024::report::{self, Solver};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const DEFAULT_INPUTS_DIR: &str = "inputs";
+
+// Answers of every part for one input file, or the error that prevented solving it
+pub struct BatchRow {
+    pub input: PathBuf,
+    pub answers: error::Result<Vec<String>>,
+}
+
+// Every `*.txt` file of `<inputs_dir>/day_<day>/`, sorted by name
+pub fn find_inputs(inputs_dir: &Path, day: usize) -> Result<Vec<PathBuf>> {
+    let day_dir = inputs_dir.join(format!("day_{}", day));
+    if !day_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut inputs = Vec::new();
+    for entry in fs::read_dir(&day_dir)
+        .context(format!("Failed to list inputs in `{}`", day_dir.display()))?
+    {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "txt") {
+            inputs.push(path);
+        }
+    }
+    inputs.sort();
+    Ok(inputs)
+}
+
+// Solves every input, a failing input is recorded in its row instead of stopping the batch.
+// With a `timeout`, inputs taking longer fail with `Error::Timeout` and the batch moves on
+pub fn run(solve: Solver, inputs: &[PathBuf], timeout: Option<Duration>) -> Vec<BatchRow> {
+    inputs
+        .iter()
+        .map(|input| BatchRow {
+            input: input.clone(),
+            answers: match timeout {
+                Some(limit) => report::solve_with_timeout(solve, input, limit),
+                None => solve(input),
+            }
+            .map(|report| {
+                report
+                    .parts
+                    .into_iter()
+                    .map(|part| part.answer.to_string())
+                    .collect()
+            }),
+        })
+        .collect()
+}
+
+pub fn render_table(rows: &[BatchRow]) -> String {
+    let part_count = rows
+        .iter()
+        .filter_map(|row| row.answers.as_ref().ok())
+        .map(|answers| answers.len())
+        .max()
+        .unwrap_or(0);
+    let mut header = vec!["Input".to_string()];
+    header.extend((1..=part_count).map(|part| format!("Part {}", part)));
+
+    let name = |row: &BatchRow| {
+        row.input
+            .file_name()
+            .map_or(row.input.display().to_string(), |name| {
+                name.to_string_lossy().to_string()
+            })
+    };
+    let table: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            let mut cells = vec![name(row)];
+            match &row.answers {
+                Ok(answers) => cells.extend(answers.iter().cloned()),
+                // parsing errors span several lines, the cell only holds the message
+                Err(err) => cells.push(format!(
+                    "error: {}",
+                    err.to_string().lines().next().unwrap_or_default()
+                )),
+            }
+            cells
+        })
+        .collect();
+
+    let mut widths: Vec<usize> = header.iter().map(|h| h.len()).collect();
+    for (row, cells) in rows.iter().zip(table.iter()) {
+        // error messages span the answer columns, they don't widen them
+        let widening = if row.answers.is_ok() { cells.len() } else { 1 };
+        for (i, cell) in cells.iter().take(widening).enumerate() {
+            widths[i] = widths[i].max(cell.len());
+        }
+    }
+
+    let format_row = |cells: &[String]| {
+        cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| match widths.get(i) {
+                Some(&width) => format!("{:<width$}", cell),
+                None => cell.clone(),
+            })
+            .collect::<Vec<String>>()
+            .join(" | ")
+            .trim_end()
+            .to_string()
+    };
+    let mut out = format_row(&header) + "\n";
+    out.push_str(
+        &widths
+            .iter()
+            .map(|&width| "-".repeat(width))
+            .collect::<Vec<String>>()
+            .join("-+-"),
+    );
+    out.push('\n');
+    for cells in table.iter() {
+        out.push_str(&format_row(cells));
+        out.push('\n');
+    }
+    // errors spanning several lines are shown in full below the table
+    for row in rows.iter() {
+        let Err(err) = &row.answers else {
+            continue;
+        };
+        let message = err.to_string();
+        if message.contains('\n') {
+            out.push_str(&format!("\n{}: {}\n", name(row), message));
+        }
+    }
+    out
+}
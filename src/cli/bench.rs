@@ -0,0 +1,270 @@
+use super::provenance::{file_sha256, git_commit};
+use advent_of_code_2024::report::Solver;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+pub const DEFAULT_HISTORY_FILE: &str = "bench_history.jsonl";
+pub const DEFAULT_SAMPLES: usize = 10;
+// Slowdown in percent above which `bench compare` reports a regression
+pub const DEFAULT_THRESHOLD: f64 = 10.0;
+
+// One line of the history file: the timings of a part of a day for a given commit and input
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchEntry {
+    pub timestamp: u64,
+    pub commit: String,
+    pub input_hash: String,
+    pub day: usize,
+    // "1", "2", ... or "total" for the whole `solve` call including input parsing
+    pub part: String,
+    pub samples: usize,
+    pub median_ns: u64,
+    pub min_ns: u64,
+    pub max_ns: u64,
+}
+
+impl BenchEntry {
+    fn key(&self) -> (usize, String, String) {
+        (self.day, self.part.clone(), self.input_hash.clone())
+    }
+}
+
+fn median(timings: &mut [Duration]) -> Duration {
+    timings.sort_unstable();
+    let mid = timings.len() / 2;
+    if timings.len().is_multiple_of(2) {
+        (timings[mid - 1] + timings[mid]) / 2
+    } else {
+        timings[mid]
+    }
+}
+
+fn to_entry(
+    day: usize,
+    part: String,
+    mut timings: Vec<Duration>,
+    commit: &str,
+    input_hash: &str,
+    timestamp: u64,
+) -> BenchEntry {
+    let median_ns = median(&mut timings).as_nanos() as u64;
+    BenchEntry {
+        timestamp,
+        commit: commit.to_string(),
+        input_hash: input_hash.to_string(),
+        day,
+        part,
+        samples: timings.len(),
+        median_ns,
+        min_ns: timings[0].as_nanos() as u64,
+        max_ns: timings[timings.len() - 1].as_nanos() as u64,
+    }
+}
+
+// Runs the solver `samples` times and summarises the timings of every part and of the whole run
+pub fn run(day: usize, solve: Solver, input: &Path, samples: usize) -> Result<Vec<BenchEntry>> {
+    if samples == 0 {
+        return Err(anyhow!("at least one sample is required to benchmark"));
+    }
+    let input_hash = file_sha256(input)?;
+    let commit = git_commit();
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let mut part_timings: BTreeMap<usize, Vec<Duration>> = BTreeMap::new();
+    let mut total_timings: Vec<Duration> = Vec::new();
+    for _ in 0..samples {
+        let start = Instant::now();
+        let report = solve(input)?;
+        total_timings.push(start.elapsed());
+        for part in report.parts {
+            part_timings
+                .entry(part.part)
+                .or_default()
+                .push(part.elapsed);
+        }
+    }
+
+    let mut entries: Vec<BenchEntry> = part_timings
+        .into_iter()
+        .map(|(part, timings)| {
+            to_entry(
+                day,
+                part.to_string(),
+                timings,
+                &commit,
+                &input_hash,
+                timestamp,
+            )
+        })
+        .collect();
+    entries.push(to_entry(
+        day,
+        "total".to_string(),
+        total_timings,
+        &commit,
+        &input_hash,
+        timestamp,
+    ));
+    Ok(entries)
+}
+
+pub fn append_history(history_path: &Path, entries: &[BenchEntry]) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(history_path)
+        .context(format!(
+            "Failed to open bench history `{}`",
+            history_path.display()
+        ))?;
+    for entry in entries {
+        writeln!(file, "{}", serde_json::to_string(entry)?)
+            .context("Failed to write bench history entry")?;
+    }
+    Ok(())
+}
+
+pub fn read_history(history_path: &Path) -> Result<Vec<BenchEntry>> {
+    let content = fs::read_to_string(history_path).context(format!(
+        "Failed to read bench history `{}`",
+        history_path.display()
+    ))?;
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).context(format!(
+                "Invalid bench history entry on line {}: `{}`",
+                i + 1,
+                line
+            ))
+        })
+        .collect()
+}
+
+pub fn print_entries(entries: &[BenchEntry]) {
+    for entry in entries {
+        println!(
+            "day {:>2} part {:>5}: median {:>12.3?} (min {:.3?}, max {:.3?}, {} samples)",
+            entry.day,
+            entry.part,
+            Duration::from_nanos(entry.median_ns),
+            Duration::from_nanos(entry.min_ns),
+            Duration::from_nanos(entry.max_ns),
+            entry.samples
+        );
+    }
+}
+
+// Latest entry of a commit for every (day, part, input) key, history is in chronological order
+fn latest_by_key(
+    history: &[BenchEntry],
+    commit: &str,
+) -> BTreeMap<(usize, String, String), BenchEntry> {
+    history
+        .iter()
+        .filter(|entry| entry.commit == commit)
+        .map(|entry| (entry.key(), entry.clone()))
+        .collect()
+}
+
+pub struct CompareOptions {
+    pub history: PathBuf,
+    pub baseline: Option<String>,
+    pub current: Option<String>,
+    pub threshold: f64,
+}
+
+// Compares the latest timings of the current commit against a baseline commit.
+// Returns the number of day/parts whose median slowed down beyond the threshold.
+pub fn compare(options: &CompareOptions) -> Result<usize> {
+    let history = read_history(&options.history)?;
+    let last_entry = history
+        .last()
+        .context("bench history is empty, run with `--bench` first")?;
+
+    let current = match &options.current {
+        Some(commit) => commit.clone(),
+        None => {
+            let commit = git_commit();
+            if history.iter().any(|entry| entry.commit == commit) {
+                commit
+            } else {
+                last_entry.commit.clone()
+            }
+        }
+    };
+    let baseline = match &options.baseline {
+        Some(commit) => commit.clone(),
+        None => history
+            .iter()
+            .rev()
+            .find(|entry| entry.commit != current)
+            .map(|entry| entry.commit.clone())
+            .context(format!(
+                "no baseline found, bench history only contains commit `{}`",
+                current
+            ))?,
+    };
+
+    let baseline_entries = latest_by_key(&history, &baseline);
+    let current_entries = latest_by_key(&history, &current);
+    if baseline_entries.is_empty() {
+        return Err(anyhow!("no bench entries for baseline `{}`", baseline));
+    }
+    if current_entries.is_empty() {
+        return Err(anyhow!("no bench entries for `{}`", current));
+    }
+
+    println!(
+        "Comparing `{}` against baseline `{}` (threshold {}%)",
+        current, baseline, options.threshold
+    );
+    let mut regressions = 0;
+    for (key, current_entry) in current_entries.iter() {
+        let baseline_entry = match baseline_entries.get(key) {
+            Some(entry) => entry,
+            None => continue,
+        };
+        let change = (current_entry.median_ns as f64 - baseline_entry.median_ns as f64)
+            / baseline_entry.median_ns.max(1) as f64
+            * 100.0;
+        let status = if change > options.threshold {
+            regressions += 1;
+            "REGRESSION"
+        } else if change < -options.threshold {
+            "improved"
+        } else {
+            "ok"
+        };
+        println!(
+            "day {:>2} part {:>5}: {:>12.3?} -> {:>12.3?} ({:>+7.1}%) {}",
+            current_entry.day,
+            current_entry.part,
+            Duration::from_nanos(baseline_entry.median_ns),
+            Duration::from_nanos(current_entry.median_ns),
+            change,
+            status
+        );
+    }
+    let skipped = current_entries
+        .keys()
+        .filter(|key| !baseline_entries.contains_key(key))
+        .count();
+    if skipped > 0 {
+        println!(
+            "{} day/parts were skipped, the baseline has no timings for them on the same input",
+            skipped
+        );
+    }
+    Ok(regressions)
+}
@@ -0,0 +1,139 @@
+use advent_of_code_2024::complexity::{self, Analysis};
+use advent_of_code_2024::generate::{Generator, Rng};
+use advent_of_code_2024::report::Solver;
+use anyhow::{anyhow, Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_SEED: u64 = 2024;
+pub const DEFAULT_SAMPLES: usize = 3;
+// Sizes stop growing once a run takes longer than this
+pub const DEFAULT_MAX_SECONDS: f64 = 1.0;
+pub const DEFAULT_MAX_POINTS: usize = 16;
+
+pub struct Options {
+    pub seed: u64,
+    pub samples: usize,
+    pub max_seconds: f64,
+    pub max_points: usize,
+}
+
+// Fastest timings of every part and of the whole run for one input size
+struct Measurement {
+    size: usize,
+    parts: BTreeMap<usize, Duration>,
+    total: Duration,
+}
+
+// Stops sampling once a run exceeds `max_seconds`, the fastest sample is kept
+fn measure(solve: Solver, input: &Path, size: usize, options: &Options) -> Result<Measurement> {
+    let mut measurement = Measurement {
+        size,
+        parts: BTreeMap::new(),
+        total: Duration::MAX,
+    };
+    for _ in 0..options.samples {
+        let start = Instant::now();
+        let report = solve(input)?;
+        let elapsed = start.elapsed();
+        measurement.total = measurement.total.min(elapsed);
+        for part in report.parts {
+            let fastest = measurement.parts.entry(part.part).or_insert(Duration::MAX);
+            *fastest = (*fastest).min(part.elapsed);
+        }
+        if elapsed.as_secs_f64() > options.max_seconds {
+            break;
+        }
+    }
+    Ok(measurement)
+}
+
+fn print_analysis(name: &str, analysis: Option<Analysis>) {
+    let Some(analysis) = analysis else {
+        println!("{:>7}: not enough measurements", name);
+        return;
+    };
+    let best = analysis.best();
+    let others: Vec<String> = analysis
+        .fits
+        .iter()
+        .filter(|fit| fit.model != best.model)
+        .take(2)
+        .map(|fit| fit.to_string())
+        .collect();
+    println!(
+        "{:>7}: {}, n^{:.2} over the largest sizes, then {}",
+        name,
+        best,
+        analysis.exponent,
+        others.join(", ")
+    );
+}
+
+// Solves generated inputs of growing sizes until a run exceeds the time budget, then fits the
+// timings of every part to the usual complexity classes
+pub fn run(day: usize, solve: Solver, generator: &Generator, options: &Options) -> Result<()> {
+    if options.samples == 0 {
+        return Err(anyhow!("at least one sample is required to measure"));
+    }
+    let input = std::env::temp_dir().join(format!(
+        "aoc_complexity_day_{}_{}.txt",
+        day,
+        std::process::id()
+    ));
+    println!("Day {}, n = {}", day, generator.size_unit);
+    println!(
+        "{:>10} {:>14} {:>14} {:>14}",
+        "n", "part 1", "part 2", "total"
+    );
+    let mut measurements: Vec<Measurement> = Vec::new();
+    for size in generator.sizes().take(options.max_points) {
+        // the same seed for every size, so that a run can be reproduced from its size alone
+        let text = (generator.generate)(size, &mut Rng::new(options.seed));
+        fs::write(&input, text).context(format!("Failed to write `{}`", input.display()))?;
+        let measurement = measure(solve, &input, size, options)
+            .context(format!("Failed to solve day {} for n = {}", day, size));
+        let measurement = match measurement {
+            Ok(measurement) => measurement,
+            Err(err) => {
+                let _ = fs::remove_file(&input);
+                return Err(err);
+            }
+        };
+        let part = |part: usize| {
+            measurement
+                .parts
+                .get(&part)
+                .map_or("-".to_string(), |elapsed| format!("{:.3?}", elapsed))
+        };
+        println!(
+            "{:>10} {:>14} {:>14} {:>14}",
+            size,
+            part(1),
+            part(2),
+            format!("{:.3?}", measurement.total)
+        );
+        let over_budget = measurement.total.as_secs_f64() > options.max_seconds;
+        measurements.push(measurement);
+        if over_budget {
+            break;
+        }
+    }
+    let _ = fs::remove_file(&input);
+
+    let series = |timing: &dyn Fn(&Measurement) -> Option<Duration>| {
+        let points: Vec<(usize, f64)> = measurements
+            .iter()
+            .filter_map(|m| timing(m).map(|elapsed| (m.size, elapsed.as_secs_f64())))
+            .collect();
+        complexity::analyse(&points)
+    };
+    println!();
+    print_analysis("part 1", series(&|m| m.parts.get(&1).copied()));
+    print_analysis("part 2", series(&|m| m.parts.get(&2).copied()));
+    print_analysis("total", series(&|m| Some(m.total)));
+    println!();
+    Ok(())
+}
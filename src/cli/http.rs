@@ -0,0 +1,203 @@
+use anyhow::{anyhow, Context, Result};
+use std::env;
+use std::io::{Read, Write};
+use std::net::{IpAddr, TcpStream};
+use std::time::Duration;
+
+// Environment variable holding the Advent of Code session cookie. Requests are plain HTTP, so it is
+// only sent to endpoints on this machine
+pub const SESSION_ENV: &str = "AOC_SESSION";
+// Environment variable holding the base url of the puzzle endpoints, `--url` takes precedence
+pub const BASE_URL_ENV: &str = "AOC_URL";
+pub const YEAR: u32 = 2024;
+
+// Endpoints follow the layout of adventofcode.com, a local stand-in only has to serve the same paths
+pub fn base_url(flag: Option<&str>) -> Result<String> {
+    match flag {
+        Some(url) => Ok(url.to_string()),
+        None => env::var(BASE_URL_ENV).map_err(|_| {
+            anyhow!(
+                "No endpoint configured, pass `--url http://...` or set `{}`",
+                BASE_URL_ENV
+            )
+        }),
+    }
+}
+
+// Page of a day's puzzle, the input is served under `<page>/input`
+pub fn puzzle_url(base_url: &str, day: usize) -> String {
+    format!("{}/{}/day/{}", base_url.trim_end_matches('/'), YEAR, day)
+}
+
+struct Url<'a> {
+    host: &'a str,
+    port: u16,
+    path: &'a str,
+}
+
+fn parse_url(url: &str) -> Result<Url<'_>> {
+    let rest = url.strip_prefix("http://").ok_or_else(|| {
+        anyhow!(
+            "Unsupported url `{}`, only plain `http://` endpoints (e.g. a local stand-in) are supported",
+            url
+        )
+    })?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let invalid_port = || format!("Invalid port in url `{}`", url);
+    // IPv6 hosts are bracketed, e.g. `[::1]:8000`, their colons are not port separators
+    let (host, port) = match authority.strip_prefix('[') {
+        Some(bracketed) => {
+            let (host, after) = bracketed
+                .split_once(']')
+                .context(format!("Unclosed `[` in url `{}`", url))?;
+            let port = match after {
+                "" => 80,
+                _ => after
+                    .strip_prefix(':')
+                    .context(invalid_port())?
+                    .parse::<u16>()
+                    .context(invalid_port())?,
+            };
+            (host, port)
+        }
+        None => match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse::<u16>().context(invalid_port())?),
+            None => (authority, 80),
+        },
+    };
+    if host.is_empty() {
+        return Err(anyhow!("Missing host in url `{}`", url));
+    }
+    Ok(Url { host, port, path })
+}
+
+fn is_loopback(host: &str) -> bool {
+    host.eq_ignore_ascii_case("localhost")
+        || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+// Value of the `Host` header, IPv6 addresses go back into brackets
+fn host_header(host: &str) -> String {
+    match host.contains(':') {
+        true => format!("[{}]", host),
+        false => host.to_string(),
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+// Minimal HTTP/1.1 GET, returns the body of a `200 OK` response
+pub fn get(url: &str) -> Result<String> {
+    let parsed = parse_url(url)?;
+    let mut stream = TcpStream::connect((parsed.host, parsed.port))
+        .context(format!("Failed to connect to `{}`", url))?;
+    stream.set_read_timeout(Some(Duration::from_secs(30)))?;
+
+    let mut request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: advent_of_code_2024\r\nConnection: close\r\n",
+        parsed.path,
+        host_header(parsed.host)
+    );
+    if let Ok(session) = env::var(SESSION_ENV) {
+        if is_loopback(parsed.host) {
+            request.push_str(&format!("Cookie: session={}\r\n", session));
+        } else {
+            eprintln!(
+                "`{}` is not sent to `{}`, the session cookie only goes to loopback endpoints",
+                SESSION_ENV, parsed.host
+            );
+        }
+    }
+    request.push_str("\r\n");
+    stream
+        .write_all(request.as_bytes())
+        .context(format!("Failed to send request to `{}`", url))?;
+
+    let mut response = Vec::new();
+    stream
+        .read_to_end(&mut response)
+        .context(format!("Failed to read response from `{}`", url))?;
+    // chunk sizes count bytes, the body is only decoded as text once complete
+    let head_end =
+        find(&response, b"\r\n\r\n").context(format!("Malformed response from `{}`", url))?;
+    let head = String::from_utf8_lossy(&response[..head_end]);
+    let body = &response[head_end + 4..];
+    let status_line = head.lines().next().unwrap_or_default();
+    let status = status_line.split_whitespace().nth(1).unwrap_or_default();
+    if status != "200" {
+        return Err(anyhow!("`{}` responded with `{}`", url, status_line));
+    }
+    let chunked = head.lines().any(|line| {
+        line.to_ascii_lowercase()
+            .starts_with("transfer-encoding: chunked")
+    });
+    let body = match chunked {
+        true => decode_chunked(body)?,
+        false => body.to_vec(),
+    };
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+fn decode_chunked(body: &[u8]) -> Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+    let mut rest = body;
+    loop {
+        let line_end = find(rest, b"\r\n").context("Malformed chunked response")?;
+        let size_line = String::from_utf8_lossy(&rest[..line_end]);
+        let size_str = size_line.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size_str, 16)
+            .context(format!("Invalid chunk size `{}`", size_line))?;
+        if size == 0 {
+            return Ok(decoded);
+        }
+        let after = &rest[line_end + 2..];
+        let chunk = after.get(..size).context("Truncated chunked response")?;
+        decoded.extend_from_slice(chunk);
+        rest = after[size..]
+            .strip_prefix(b"\r\n")
+            .unwrap_or(&after[size..]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_are_counted_in_bytes() {
+        // `é` is 2 bytes, an invalid byte is replaced only once the body is complete
+        let body = b"3\r\n\xc3\xa9a\r\n4;ext=1\r\nb\xffcd\r\n0\r\n\r\n";
+        let decoded = decode_chunked(body).unwrap();
+        assert_eq!(decoded, b"\xc3\xa9ab\xffcd");
+        assert_eq!(String::from_utf8_lossy(&decoded), "éab\u{fffd}cd");
+        assert!(decode_chunked(b"5\r\nab").is_err());
+    }
+
+    #[test]
+    fn urls_with_ipv6_hosts() {
+        let url = parse_url("http://[::1]/2024/day/1").unwrap();
+        assert_eq!((url.host, url.port, url.path), ("::1", 80, "/2024/day/1"));
+        let url = parse_url("http://[::1]:8000").unwrap();
+        assert_eq!((url.host, url.port, url.path), ("::1", 8000, "/"));
+        assert!(is_loopback(url.host));
+        assert_eq!(host_header(url.host), "[::1]");
+        assert!(parse_url("http://[::1/").is_err());
+        assert!(parse_url("http://[::1]x/").is_err());
+    }
+
+    #[test]
+    fn urls_with_names() {
+        let url = parse_url("http://localhost:8000/leaderboard.json").unwrap();
+        assert_eq!((url.host, url.port), ("localhost", 8000));
+        assert_eq!(parse_url("http://example.com").unwrap().port, 80);
+        assert!(!is_loopback("example.com"));
+        assert!(parse_url("https://example.com").is_err());
+    }
+}
@@ -0,0 +1,179 @@
+use super::http;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Deserialize)]
+struct Star {
+    get_star_ts: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Member {
+    id: u64,
+    name: Option<String>,
+    #[serde(default)]
+    stars: u32,
+    #[serde(default)]
+    local_score: u32,
+    // day -> part -> star
+    #[serde(default)]
+    completion_day_level: HashMap<String, HashMap<String, Star>>,
+}
+
+impl Member {
+    // Anonymous users have no name in the export
+    fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("(anonymous user #{})", self.id),
+        }
+    }
+
+    fn star_ts(&self, day: u32, part: u32) -> Option<i64> {
+        self.completion_day_level
+            .get(&day.to_string())
+            .and_then(|parts| parts.get(&part.to_string()))
+            .map(|star| star.get_star_ts)
+    }
+}
+
+// Private leaderboard JSON as exported from `/<year>/leaderboard/private/view/<id>.json`
+#[derive(Debug, Deserialize)]
+pub struct Leaderboard {
+    event: String,
+    members: HashMap<String, Member>,
+}
+
+impl Leaderboard {
+    pub fn from_file(file_path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(file_path).context(format!(
+            "Failed to read leaderboard `{}`",
+            file_path.display()
+        ))?;
+        Self::parse(&content)
+    }
+
+    pub fn from_url(url: &str) -> Result<Self> {
+        Self::parse(&http::get(url)?)
+    }
+
+    fn parse(content: &str) -> Result<Self> {
+        serde_json::from_str(content).context("Invalid private leaderboard JSON")
+    }
+
+    // Members ranked by local score, ties broken by stars then by name
+    fn ranked_members(&self) -> Vec<&Member> {
+        let mut members: Vec<&Member> = self.members.values().collect();
+        members.sort_by(|a, b| {
+            b.local_score
+                .cmp(&a.local_score)
+                .then(b.stars.cmp(&a.stars))
+                .then(a.display_name().cmp(&b.display_name()))
+        });
+        members
+    }
+
+    pub fn render(&self) -> String {
+        let members = self.ranked_members();
+        let name_width = members
+            .iter()
+            .map(|m| m.display_name().chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(4);
+        let mut out = format!("Private leaderboard {}\n\n", self.event);
+        out.push_str(&format!(
+            "{:>4}  {:<name_width$}  {:>5}  {:>5}\n",
+            "Rank", "Name", "Score", "Stars"
+        ));
+        for (rank, member) in members.iter().enumerate() {
+            out.push_str(&format!(
+                "{:>4}  {:<name_width$}  {:>5}  {:>5}\n",
+                rank + 1,
+                member.display_name(),
+                member.local_score,
+                member.stars
+            ));
+        }
+
+        for member in members.iter().filter(|m| m.stars > 0) {
+            out.push_str(&format!("\n{}\n", member.display_name()));
+            out.push_str(&format!(
+                "{:>6}  {:<19}  {:<19}  {:>12}\n",
+                "Day", "Part 1 (UTC)", "Part 2 (UTC)", "Part 2 delta"
+            ));
+            for day in 1..=25 {
+                let part_1 = member.star_ts(day, 1);
+                let part_2 = member.star_ts(day, 2);
+                if part_1.is_none() && part_2.is_none() {
+                    continue;
+                }
+                let delta = match (part_1, part_2) {
+                    (Some(first), Some(second)) => format_duration(second - first),
+                    _ => "-".to_string(),
+                };
+                out.push_str(&format!(
+                    "{:>6}  {:<19}  {:<19}  {:>12}\n",
+                    day,
+                    part_1.map_or("-".to_string(), format_timestamp),
+                    part_2.map_or("-".to_string(), format_timestamp),
+                    delta
+                ));
+            }
+        }
+        out
+    }
+}
+
+// Date of the proleptic Gregorian calendar for a number of days since 1970-01-01 (Howard Hinnant's algorithm)
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+fn format_timestamp(ts: i64) -> String {
+    let (year, month, day) = civil_from_days(ts.div_euclid(86400));
+    let seconds = ts.rem_euclid(86400);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+fn format_duration(seconds: i64) -> String {
+    let sign = if seconds < 0 { "-" } else { "" };
+    let seconds = seconds.abs();
+    let days = seconds / 86400;
+    let clock = format!(
+        "{:02}:{:02}:{:02}",
+        seconds % 86400 / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    );
+    if days > 0 {
+        format!("{}{}d {}", sign, days, clock)
+    } else {
+        format!("{}{}", sign, clock)
+    }
+}
@@ -0,0 +1,206 @@
+use super::provenance::{file_sha256, git_commit};
+use advent_of_code_2024::answer::Answer;
+use advent_of_code_2024::report::{Report, Solver};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const DEFAULT_MANIFEST_FILE: &str = "run_manifest.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartManifest {
+    pub part: usize,
+    pub description: String,
+    pub answer: Answer,
+    pub elapsed_ns: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayManifest {
+    pub day: usize,
+    // Commit and time of the run that solved the day, manifests merge days from several runs.
+    // Missing from older manifests, they are then those of the manifest
+    #[serde(default)]
+    pub commit: String,
+    #[serde(default)]
+    pub created_at: u64,
+    pub input: PathBuf,
+    pub input_sha256: String,
+    pub elapsed_ns: u64,
+    pub parts: Vec<PartManifest>,
+}
+
+// Records which code and which input produced the answers of a run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub crate_version: String,
+    pub commit: String,
+    pub created_at: u64,
+    pub days: Vec<DayManifest>,
+}
+
+impl Default for Manifest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Manifest {
+    pub fn new() -> Self {
+        Self {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            commit: git_commit(),
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            days: Vec::new(),
+        }
+    }
+
+    pub fn add_day(
+        &mut self,
+        day: usize,
+        input: &Path,
+        report: &Report,
+        elapsed: Duration,
+    ) -> Result<()> {
+        self.days.push(DayManifest {
+            day,
+            commit: self.commit.clone(),
+            created_at: self.created_at,
+            input: input.to_path_buf(),
+            input_sha256: file_sha256(input)?,
+            elapsed_ns: elapsed.as_nanos() as u64,
+            parts: report
+                .parts
+                .iter()
+                .map(|part| PartManifest {
+                    part: part.part,
+                    description: part.description.clone(),
+                    answer: part.answer.clone(),
+                    elapsed_ns: part.elapsed.as_nanos() as u64,
+                })
+                .collect(),
+        });
+        Ok(())
+    }
+
+    pub fn write(&self, manifest_path: &Path) -> Result<()> {
+        fs::write(manifest_path, serde_json::to_string_pretty(self)?).context(format!(
+            "Failed to write run manifest `{}`",
+            manifest_path.display()
+        ))
+    }
+
+    pub fn read(manifest_path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(manifest_path).context(format!(
+            "Failed to read run manifest `{}`",
+            manifest_path.display()
+        ))?;
+        let mut manifest: Self = serde_json::from_str(&content).context(format!(
+            "Invalid run manifest `{}`",
+            manifest_path.display()
+        ))?;
+        for day in manifest.days.iter_mut().filter(|day| day.commit.is_empty()) {
+            day.commit = manifest.commit.clone();
+            day.created_at = manifest.created_at;
+        }
+        Ok(manifest)
+    }
+
+    // Keeps the days of `previous` this manifest did not solve again, e.g. after a run of a single day
+    pub fn merge(&mut self, previous: Manifest) {
+        for day in previous.days {
+            if !self.days.iter().any(|solved| solved.day == day.day) {
+                self.days.push(day);
+            }
+        }
+        self.days.sort_by_key(|day| day.day);
+    }
+}
+
+// Re-runs every day of the manifest with the current build and returns the number of mismatches.
+// `find_solver` returns `None` for days this build does not know how to solve.
+pub fn replay(manifest: &Manifest, find_solver: impl Fn(usize) -> Option<Solver>) -> Result<usize> {
+    let version = env!("CARGO_PKG_VERSION");
+    let commit = git_commit();
+    println!(
+        "Replaying manifest from version {} at `{}` with version {} at `{}`",
+        manifest.crate_version, manifest.commit, version, commit
+    );
+    let mut mismatches = 0;
+    for recorded in manifest.days.iter() {
+        let solve = match find_solver(recorded.day) {
+            Some(solve) => solve,
+            None => {
+                println!("day {}: MISMATCH, not solved by this build", recorded.day);
+                mismatches += 1;
+                continue;
+            }
+        };
+        let input_sha256 = match file_sha256(&recorded.input) {
+            Ok(hash) => hash,
+            Err(_) => {
+                println!(
+                    "day {}: MISMATCH, input `{}` is missing",
+                    recorded.day,
+                    recorded.input.display()
+                );
+                mismatches += 1;
+                continue;
+            }
+        };
+        if input_sha256 != recorded.input_sha256 {
+            println!(
+                "day {}: MISMATCH, input `{}` has changed (sha256 {} instead of {})",
+                recorded.day,
+                recorded.input.display(),
+                input_sha256,
+                recorded.input_sha256
+            );
+            mismatches += 1;
+            continue;
+        }
+        let report = match solve(&recorded.input) {
+            Ok(report) => report,
+            Err(err) => {
+                println!("day {}: MISMATCH, failed to solve: {}", recorded.day, err);
+                mismatches += 1;
+                continue;
+            }
+        };
+        for recorded_part in recorded.parts.iter() {
+            let answer = report
+                .parts
+                .iter()
+                .find(|part| part.part == recorded_part.part)
+                .map(|part| &part.answer);
+            match answer {
+                Some(answer) if *answer == recorded_part.answer => {
+                    println!(
+                        "day {} part {}: ok ({})",
+                        recorded.day, recorded_part.part, answer
+                    );
+                }
+                Some(answer) => {
+                    println!(
+                        "day {} part {}: MISMATCH, expected {} got {}",
+                        recorded.day, recorded_part.part, recorded_part.answer, answer
+                    );
+                    mismatches += 1;
+                }
+                None => {
+                    println!(
+                        "day {} part {}: MISMATCH, part is no longer solved",
+                        recorded.day, recorded_part.part
+                    );
+                    mismatches += 1;
+                }
+            }
+        }
+    }
+    Ok(mismatches)
+}
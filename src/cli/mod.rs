@@ -0,0 +1,11 @@
+// Tooling around the solvers that only the binary needs
+pub mod animate;
+pub mod batch;
+pub mod bench;
+pub mod complexity;
+pub mod http;
+pub mod leaderboard;
+pub mod manifest;
+pub mod provenance;
+pub mod puzzle;
+pub mod serve;
@@ -0,0 +1,41 @@
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+fn run_git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// Short hash of the checked out commit, suffixed with `-dirty` when tracked files were modified
+// so that measurements of work in progress are not mixed up with the commit they started from
+pub fn git_commit() -> String {
+    let commit = match run_git(&["rev-parse", "--short", "HEAD"]) {
+        Some(commit) => commit,
+        None => return "unknown".to_string(),
+    };
+    match run_git(&["status", "--porcelain", "--untracked-files=no"]) {
+        Some(status) if !status.is_empty() => format!("{}-dirty", commit),
+        _ => commit,
+    }
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+pub fn file_sha256(file_path: &Path) -> Result<String> {
+    let bytes = fs::read(file_path).context(format!(
+        "Failed to read `{}` for hashing",
+        file_path.display()
+    ))?;
+    Ok(sha256_hex(&bytes))
+}
@@ -0,0 +1,435 @@
+use super::http;
+use advent_of_code_2024::cache;
+use advent_of_code_2024::render::{Color, Style};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Puzzle descriptions are fetched once and kept in `<cache dir>/puzzles/day_N.html`, the cache dir is
+// the one given to `--cache` or `.cache`
+const PUZZLE_CACHE_DIR: &str = "puzzles";
+pub const DEFAULT_WIDTH: usize = 100;
+
+const TITLE_STYLE: Style = Style::fg(Color::Green).bold();
+const EMPHASIS_STYLE: Style = Style::fg(Color::White).bold();
+const STAR_STYLE: Style = Style::fg(Color::Yellow).bold();
+const CODE_STYLE: Style = Style::fg(Color::Cyan);
+
+fn cache_file(day: usize) -> PathBuf {
+    cache::dir()
+        .unwrap_or(Path::new(cache::DEFAULT_CACHE_DIR))
+        .join(PUZZLE_CACHE_DIR)
+        .join(format!("day_{}.html", day))
+}
+
+// HTML of the day's puzzle, from the cache unless `refresh` is set
+pub fn fetch(day: usize, base_url: &str, refresh: bool) -> Result<String> {
+    let path = cache_file(day);
+    if !refresh {
+        if let Ok(html) = fs::read_to_string(&path) {
+            return Ok(html);
+        }
+    }
+    let html = http::get(&http::puzzle_url(base_url, day))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).context(format!("Failed to create `{}`", dir.display()))?;
+    }
+    fs::write(&path, &html).context(format!("Failed to write `{}`", path.display()))?;
+    Ok(html)
+}
+
+enum Token<'a> {
+    // Tag name in lowercase and the raw attributes
+    Open(String, &'a str),
+    Close(String),
+    Text(&'a str),
+}
+
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+    while !rest.is_empty() {
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.split_once("-->").map_or("", |(_, after)| after);
+            continue;
+        }
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            tokens.push(Token::Text(&rest[..end]));
+            rest = &rest[end..];
+            continue;
+        }
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = rest[1..end].trim_end_matches('/');
+        rest = &rest[end + 1..];
+        let (name, attributes) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+        let name = name.to_ascii_lowercase();
+        if let Some(name) = name.strip_prefix('/') {
+            tokens.push(Token::Close(name.to_string()));
+        } else if name == "script" || name == "style" {
+            // their content is not text
+            let closing = format!("</{}", name);
+            let end = rest
+                .to_ascii_lowercase()
+                .find(&closing)
+                .unwrap_or(rest.len());
+            rest = &rest[end..];
+        } else if !name.starts_with('!') {
+            tokens.push(Token::Open(name, attributes));
+        }
+    }
+    tokens
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest
+            .find(';')
+            .filter(|&end| end <= 10)
+            .map(|end| (&rest[1..end], end));
+        let char = entity.and_then(|(name, _)| match name {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => match name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+                None => name
+                    .strip_prefix('#')?
+                    .parse()
+                    .ok()
+                    .and_then(char::from_u32),
+            },
+        });
+        match (char, entity) {
+            (Some(char), Some((_, end))) => {
+                decoded.push(char);
+                rest = &rest[end + 1..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+// Tokens of the puzzle descriptions, the `<article>`s of the page (one per part that is unlocked),
+// or the whole page when there are none
+fn description(html: &str) -> Vec<Token<'_>> {
+    let tokens = tokenize(html);
+    let mut in_article = false;
+    let article_tokens: Vec<usize> = tokens
+        .iter()
+        .enumerate()
+        .filter_map(|(i, token)| {
+            match token {
+                Token::Open(name, _) if name == "article" => in_article = true,
+                Token::Close(name) if name == "article" => in_article = false,
+                _ => {}
+            }
+            in_article.then_some(i)
+        })
+        .collect();
+    if article_tokens.is_empty() {
+        return tokens;
+    }
+    tokens
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| article_tokens.binary_search(i).is_ok())
+        .map(|(_, token)| token)
+        .collect()
+}
+
+// Runs of text sharing a style, a word is never split across lines
+type Word = Vec<(String, Style)>;
+
+struct TextRenderer {
+    width: usize,
+    color: bool,
+    output: String,
+    // Words of the paragraph being built
+    words: Vec<Word>,
+    // Whether the next character starts a new word
+    word_break: bool,
+    styles: Vec<Style>,
+    // Text of the `<pre>` being built, `None` outside of one
+    pre: Option<Vec<(char, Style)>>,
+    in_list_item: bool,
+}
+
+impl TextRenderer {
+    fn style(&self) -> Style {
+        self.styles
+            .iter()
+            .fold(Style::PLAIN, |style, &other| style.merge(other))
+    }
+
+    fn paint(&self, text: &str, style: Style) -> String {
+        if self.color && style != Style::PLAIN {
+            format!("{}{}\x1b[0m", style.escape(), text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        let style = self.style();
+        if let Some(pre) = self.pre.as_mut() {
+            pre.extend(text.chars().map(|char| (char, style)));
+            return;
+        }
+        for char in text.chars() {
+            if char.is_whitespace() {
+                self.word_break = true;
+                continue;
+            }
+            if self.word_break || self.words.is_empty() {
+                self.words.push(Vec::new());
+                self.word_break = false;
+            }
+            let word = self.words.last_mut().expect("a word was just pushed");
+            match word.last_mut() {
+                Some((text, last_style)) if *last_style == style => text.push(char),
+                _ => word.push((char.to_string(), style)),
+            }
+        }
+    }
+
+    // Markers standing in for styles without colours, e.g. `*emphasis*`
+    fn push_marker(&mut self, marker: &str) {
+        if !self.color && self.pre.is_none() {
+            self.push_text(marker);
+        }
+    }
+
+    // Word-wraps the paragraph being built
+    fn flush(&mut self) {
+        if self.words.is_empty() {
+            return;
+        }
+        let (first_prefix, prefix) = if self.in_list_item {
+            ("  - ", "    ")
+        } else {
+            ("", "")
+        };
+        let mut line = first_prefix.to_string();
+        let mut line_len = first_prefix.len();
+        let mut line_has_words = false;
+        for word in std::mem::take(&mut self.words) {
+            let word_len: usize = word.iter().map(|(text, _)| text.chars().count()).sum();
+            if line_has_words && line_len + 1 + word_len > self.width {
+                self.output.push_str(&line);
+                self.output.push('\n');
+                line = prefix.to_string();
+                line_len = prefix.len();
+                line_has_words = false;
+            }
+            if line_has_words {
+                line.push(' ');
+                line_len += 1;
+            }
+            for (text, style) in word.iter() {
+                line.push_str(&self.paint(text, *style));
+            }
+            line_len += word_len;
+            line_has_words = true;
+        }
+        self.output.push_str(line.trim_end());
+        self.output.push('\n');
+        if !self.in_list_item {
+            self.output.push('\n');
+        }
+        self.word_break = false;
+    }
+
+    fn flush_pre(&mut self) {
+        let Some(pre) = self.pre.take() else {
+            return;
+        };
+        let text: String = pre.iter().map(|(char, _)| char).collect();
+        let mut chars = pre.into_iter();
+        for line in text.trim_end_matches('\n').split('\n') {
+            let mut painted = "    ".to_string();
+            let mut run = String::new();
+            let mut run_style = CODE_STYLE;
+            for (char, style) in chars.by_ref().take(line.chars().count()) {
+                let style = CODE_STYLE.merge(style);
+                if style != run_style && !run.is_empty() {
+                    painted.push_str(&self.paint(&run, run_style));
+                    run.clear();
+                }
+                run_style = style;
+                run.push(char);
+            }
+            painted.push_str(&self.paint(&run, run_style));
+            self.output.push_str(&painted);
+            self.output.push('\n');
+            // the newline ending the line
+            chars.next();
+        }
+        self.output.push('\n');
+    }
+
+    fn open(&mut self, name: &str, attributes: &str) {
+        match name {
+            "p" | "h2" | "ul" | "div" | "article" => self.flush(),
+            "li" => {
+                self.flush();
+                self.in_list_item = true;
+            }
+            "pre" => {
+                self.flush();
+                self.pre = Some(Vec::new());
+            }
+            "br" => self.flush(),
+            _ => {}
+        }
+        let style = match name {
+            "h2" => TITLE_STYLE,
+            "em" if attributes.contains("star") => STAR_STYLE,
+            "em" | "b" | "strong" => EMPHASIS_STYLE,
+            "code" if self.pre.is_none() => CODE_STYLE,
+            _ => return,
+        };
+        match name {
+            "em" | "b" | "strong" => self.push_marker("*"),
+            "code" => self.push_marker("`"),
+            _ => {}
+        }
+        self.styles.push(style);
+    }
+
+    fn close(&mut self, name: &str) {
+        let styled = match name {
+            "h2" | "em" | "b" | "strong" => true,
+            // code blocks are styled as a whole by `flush_pre`
+            "code" => self.pre.is_none(),
+            _ => false,
+        };
+        if styled {
+            self.styles.pop();
+            match name {
+                "em" | "b" | "strong" => self.push_marker("*"),
+                "code" => self.push_marker("`"),
+                _ => {}
+            }
+        }
+        match name {
+            "p" | "h2" | "div" | "article" => self.flush(),
+            "li" => {
+                self.flush();
+                self.in_list_item = false;
+            }
+            "ul" => {
+                self.flush();
+                self.output.push('\n');
+            }
+            "pre" => self.flush_pre(),
+            _ => {}
+        }
+    }
+}
+
+// Puzzle description as terminal text wrapped at `width` columns. Emphasis and code are coloured,
+// or marked with `*` and `` ` `` without colours, and code blocks are indented
+pub fn to_text(html: &str, width: usize, color: bool) -> String {
+    let mut renderer = TextRenderer {
+        width,
+        color,
+        output: String::new(),
+        words: Vec::new(),
+        word_break: false,
+        styles: Vec::new(),
+        pre: None,
+        in_list_item: false,
+    };
+    for token in description(html) {
+        match token {
+            Token::Open(name, attributes) => renderer.open(&name, attributes),
+            Token::Close(name) => renderer.close(&name),
+            Token::Text(text) => renderer.push_text(&decode_entities(text)),
+        }
+    }
+    renderer.flush();
+    renderer.flush_pre();
+    renderer.output.trim_end().to_string() + "\n"
+}
+
+// Width of every line, blocks drawn over an earlier example have the same shape
+fn shape(text: &str) -> Vec<usize> {
+    text.lines().map(|line| line.chars().count()).collect()
+}
+
+// Code blocks introduced by a paragraph mentioning an example, or the first code block when no
+// paragraph does. Blocks repeating an earlier example or annotating it, e.g. the same grid with the
+// matches highlighted, are not inputs and are left out
+pub fn examples(html: &str) -> Vec<String> {
+    let mut blocks: Vec<(String, bool)> = Vec::new();
+    let mut paragraph = String::new();
+    let mut pre: Option<String> = None;
+    for token in description(html) {
+        match token {
+            Token::Open(name, _) if name == "p" => paragraph.clear(),
+            Token::Open(name, _) if name == "pre" => pre = Some(String::new()),
+            Token::Close(name) if name == "pre" => {
+                if let Some(text) = pre.take() {
+                    let introduced = paragraph.to_ascii_lowercase().contains("example");
+                    blocks.push((text, introduced));
+                }
+            }
+            Token::Text(text) => match pre.as_mut() {
+                Some(pre) => pre.push_str(&decode_entities(text)),
+                None => paragraph.push_str(&decode_entities(text)),
+            },
+            _ => {}
+        }
+    }
+    let mut examples: Vec<String> = Vec::new();
+    for (text, _) in blocks.iter().filter(|(_, introduced)| *introduced) {
+        if !examples.iter().any(|example| shape(example) == shape(text)) {
+            examples.push(text.clone());
+        }
+    }
+    if examples.is_empty() {
+        blocks.into_iter().take(1).map(|(text, _)| text).collect()
+    } else {
+        examples
+    }
+}
+
+// Writes the `src/day_N/example_K.txt` fixtures, existing ones are kept unless `force` is set so
+// that edited fixtures survive. Returns every path and whether it was written
+pub fn write_examples(
+    day: usize,
+    examples: &[String],
+    force: bool,
+) -> Result<Vec<(PathBuf, bool)>> {
+    let dir = PathBuf::from(format!("src/day_{}", day));
+    fs::create_dir_all(&dir).context(format!("Failed to create `{}`", dir.display()))?;
+    examples
+        .iter()
+        .enumerate()
+        .map(|(i, example)| {
+            let path = dir.join(format!("example_{}.txt", i + 1));
+            if path.exists() && !force {
+                return Ok((path, false));
+            }
+            let mut text = example.trim_end_matches('\n').to_string();
+            text.push('\n');
+            fs::write(&path, text).context(format!("Failed to write `{}`", path.display()))?;
+            Ok((path, true))
+        })
+        .collect()
+}
@@ -0,0 +1,373 @@
+use super::bench::{read_history, BenchEntry};
+use super::manifest::{DayManifest, Manifest};
+use advent_of_code_2024::image::{Image, ImageOptions};
+use advent_of_code_2024::render::Renderer;
+use anyhow::{anyhow, Context, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+pub const DEFAULT_PORT: u16 = 8024;
+// Benchmark runs drawn in a trend line
+const TREND_LENGTH: usize = 30;
+// How often the page asks whether a run completed
+const POLL_MILLISECONDS: u64 = 2000;
+const MAX_REQUEST_BYTES: usize = 8192;
+
+pub struct Options {
+    pub port: u16,
+    pub manifest: PathBuf,
+    pub history: PathBuf,
+}
+
+struct Dashboard {
+    options: Options,
+    renderer: fn(usize) -> Option<Renderer>,
+    // Modification times of the manifest and of the history, the page reloads when it changes
+    version: String,
+    // Latest recorded run of every day, also when the manifest was replaced by one with fewer days
+    days: BTreeMap<usize, DayManifest>,
+    history: Vec<BenchEntry>,
+    // PNG of a day for an input hash
+    images: HashMap<(usize, String), Vec<u8>>,
+}
+
+fn modified_nanos(path: &Path) -> u128 {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |time| time.as_nanos())
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for char in text.chars() {
+        match char {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(char),
+        }
+    }
+    escaped
+}
+
+fn format_nanos(nanos: u64) -> String {
+    format!("{:.3?}", Duration::from_nanos(nanos))
+}
+
+// Time of the day of a unix timestamp
+fn format_time(timestamp: u64) -> String {
+    let seconds = timestamp % 86400;
+    format!(
+        "{:02}:{:02}:{:02} UTC",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+// Polyline of the timings scaled to the box, the fastest run at the bottom
+fn sparkline(timings: &[u64]) -> String {
+    const WIDTH: f64 = 160.0;
+    const HEIGHT: f64 = 32.0;
+    let min = timings.iter().copied().min().unwrap_or(0) as f64;
+    let max = timings.iter().copied().max().unwrap_or(0) as f64;
+    let step = WIDTH / (timings.len().max(2) - 1) as f64;
+    let points: Vec<String> = timings
+        .iter()
+        .enumerate()
+        .map(|(i, &timing)| {
+            let ratio = if max > min {
+                (timing as f64 - min) / (max - min)
+            } else {
+                0.5
+            };
+            format!(
+                "{:.1},{:.1}",
+                i as f64 * step,
+                HEIGHT - 2.0 - ratio * (HEIGHT - 4.0)
+            )
+        })
+        .collect();
+    format!(
+        "<svg width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\"><polyline points=\"{}\"/></svg>",
+        points.join(" "),
+        w = WIDTH,
+        h = HEIGHT
+    )
+}
+
+impl Dashboard {
+    // Reloads the files when they changed since the last request
+    fn refresh(&mut self) {
+        let version = format!(
+            "{}-{}",
+            modified_nanos(&self.options.manifest),
+            modified_nanos(&self.options.history)
+        );
+        if version == self.version {
+            return;
+        }
+        self.version = version;
+        // a missing file is not an error, the page just waits for the first run
+        if self.options.manifest.exists() {
+            match Manifest::read(&self.options.manifest) {
+                Ok(manifest) => {
+                    for day in manifest.days {
+                        self.days.insert(day.day, day);
+                    }
+                }
+                Err(err) => eprintln!("{:#}", err),
+            }
+        }
+        if self.options.history.exists() {
+            match read_history(&self.options.history) {
+                Ok(history) => self.history = history,
+                Err(err) => eprintln!("{:#}", err),
+            }
+        }
+    }
+
+    // Medians of the latest runs of a part, on the input of its last run only
+    fn trend(&self, day: usize, part: &str) -> Vec<u64> {
+        let entries: Vec<&BenchEntry> = self
+            .history
+            .iter()
+            .filter(|entry| entry.day == day && entry.part == part)
+            .collect();
+        let Some(last) = entries.last() else {
+            return Vec::new();
+        };
+        let timings: Vec<u64> = entries
+            .iter()
+            .filter(|entry| entry.input_hash == last.input_hash)
+            .map(|entry| entry.median_ns)
+            .collect();
+        timings[timings.len().saturating_sub(TREND_LENGTH)..].to_vec()
+    }
+
+    fn trend_row(&self, html: &mut String, day: usize, part: &str) {
+        let timings = self.trend(day, part);
+        let Some(&last) = timings.last() else {
+            return;
+        };
+        let change = match timings.len() {
+            0 | 1 => String::new(),
+            // timings below the clock resolution are 0, no ratio can be computed from them
+            len if timings[len - 2] == 0 => "n/a".to_string(),
+            len => {
+                let previous = timings[len - 2] as f64;
+                let percent = (last as f64 - previous) / previous * 100.0;
+                let class = if percent > 0.0 { "slower" } else { "faster" };
+                format!("<span class=\"{}\">{:+.1}%</span>", class, percent)
+            }
+        };
+        let _ = write!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{} {}</td></tr>",
+            part,
+            sparkline(&timings),
+            timings.len(),
+            format_nanos(last),
+            change
+        );
+    }
+
+    fn page(&self) -> String {
+        let mut html = String::new();
+        let _ = write!(
+            html,
+            "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>Advent of Code 2024</title>\
+             <style>{}</style></head><body><h1>Advent of Code 2024</h1>",
+            STYLE
+        );
+        if self.days.is_empty() {
+            let _ = write!(
+                html,
+                "<p>No run recorded in <code>{}</code> yet, this page reloads once a run completes.</p>",
+                escape_html(&self.options.manifest.display().to_string())
+            );
+        }
+        for (day, run) in self.days.iter() {
+            let _ = write!(
+                html,
+                "<section><h2>Day {}</h2><p class=\"meta\">{} at <code>{}</code>, input <code>{}</code></p>\
+                 <table><tr><th>part</th><th></th><th>answer</th><th>time</th></tr>",
+                day,
+                format_time(run.created_at),
+                escape_html(&run.commit),
+                escape_html(&run.input.display().to_string())
+            );
+            for part in run.parts.iter() {
+                let _ = write!(
+                    html,
+                    "<tr><td>{}</td><td>{}</td><td><pre>{}</pre></td><td>{}</td></tr>",
+                    part.part,
+                    escape_html(&part.description),
+                    escape_html(&part.answer.canonical()),
+                    format_nanos(part.elapsed_ns)
+                );
+            }
+            let _ = write!(
+                html,
+                "<tr><td>total</td><td>including input parsing</td><td></td><td>{}</td></tr></table>",
+                format_nanos(run.elapsed_ns)
+            );
+            let mut trends = String::new();
+            for part in run.parts.iter() {
+                self.trend_row(&mut trends, *day, &part.part.to_string());
+            }
+            self.trend_row(&mut trends, *day, "total");
+            if !trends.is_empty() {
+                let _ = write!(
+                    html,
+                    "<h3>Benchmarks</h3><table><tr><th>part</th><th>median</th><th>runs</th><th>latest</th></tr>{}</table>",
+                    trends
+                );
+            }
+            if (self.renderer)(*day).is_some() {
+                // the hash in the address makes the browser fetch the image again when the input changes
+                let _ = write!(
+                    html,
+                    "<img src=\"/day/{}.png?input={}\" alt=\"grid of day {}\">",
+                    day, run.input_sha256, day
+                );
+            }
+            html.push_str("</section>");
+        }
+        let _ = write!(
+            html,
+            "<script>const version = \"{}\";\
+             setInterval(() => fetch(\"/version\").then(r => r.text())\
+             .then(v => {{ if (v !== version) location.reload(); }}).catch(() => {{}}), {});</script>\
+             </body></html>",
+            self.version, POLL_MILLISECONDS
+        );
+        html
+    }
+
+    fn image(&mut self, day: usize) -> Result<Option<Vec<u8>>> {
+        let (Some(render), Some(run)) = ((self.renderer)(day), self.days.get(&day)) else {
+            return Ok(None);
+        };
+        let key = (day, run.input_sha256.clone());
+        if !self.images.contains_key(&key) {
+            let canvas = render(&run.input).context(format!("Failed to render day {}", day))?;
+            let png = Image::from_canvas(&canvas, &ImageOptions::default()).to_png();
+            self.images.insert(key.clone(), png);
+        }
+        Ok(self.images.get(&key).cloned())
+    }
+
+    // Status, content type and body of the response to a path
+    fn route(&mut self, path: &str) -> (&'static str, &'static str, Vec<u8>) {
+        self.refresh();
+        const HTML: &str = "text/html; charset=utf-8";
+        const TEXT: &str = "text/plain; charset=utf-8";
+        let not_found = ("404 Not Found", TEXT, b"not found".to_vec());
+        match path {
+            "/" => ("200 OK", HTML, self.page().into_bytes()),
+            "/version" => ("200 OK", TEXT, self.version.clone().into_bytes()),
+            _ => {
+                let day = path
+                    .strip_prefix("/day/")
+                    .and_then(|rest| rest.strip_suffix(".png"))
+                    .and_then(|day| day.parse::<usize>().ok());
+                let Some(day) = day else {
+                    return not_found;
+                };
+                match self.image(day) {
+                    Ok(Some(png)) => ("200 OK", "image/png", png),
+                    Ok(None) => not_found,
+                    Err(err) => (
+                        "500 Internal Server Error",
+                        TEXT,
+                        format!("{:#}", err).into_bytes(),
+                    ),
+                }
+            }
+        }
+    }
+
+    fn handle(&mut self, mut stream: TcpStream) -> Result<()> {
+        stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+        let mut request = Vec::new();
+        let mut buffer = [0; 1024];
+        // only the request line matters, the headers are read so that the client is not reset
+        while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+            let read = stream.read(&mut buffer)?;
+            if read == 0 || request.len() > MAX_REQUEST_BYTES {
+                break;
+            }
+            request.extend_from_slice(&buffer[..read]);
+        }
+        let request = String::from_utf8_lossy(&request);
+        let mut words = request
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .split_whitespace();
+        let (status, content_type, body) = match (words.next(), words.next()) {
+            (Some("GET"), Some(target)) => self.route(target.split('?').next().unwrap_or_default()),
+            _ => (
+                "405 Method Not Allowed",
+                "text/plain; charset=utf-8",
+                b"only GET is supported".to_vec(),
+            ),
+        };
+        write!(
+            stream,
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+            status,
+            content_type,
+            body.len()
+        )?;
+        stream.write_all(&body)?;
+        Ok(())
+    }
+}
+
+// Serves the dashboard on localhost until the process is stopped, one request at a time.
+// `renderer` tells which days have a grid to draw
+pub fn run(options: Options, renderer: fn(usize) -> Option<Renderer>) -> Result<()> {
+    let address = format!("127.0.0.1:{}", options.port);
+    let listener = TcpListener::bind(&address)
+        .map_err(|err| anyhow!("Failed to listen on {}: {}", address, err))?;
+    println!("Dashboard served on http://{}", address);
+    let mut dashboard = Dashboard {
+        options,
+        renderer,
+        version: String::new(),
+        days: BTreeMap::new(),
+        history: Vec::new(),
+        images: HashMap::new(),
+    };
+    for stream in listener.incoming() {
+        let result = stream
+            .map_err(anyhow::Error::from)
+            .and_then(|stream| dashboard.handle(stream));
+        // a client going away does not stop the server
+        if let Err(err) = result {
+            eprintln!("request failed: {:#}", err);
+        }
+    }
+    Ok(())
+}
+
+const STYLE: &str =
+    "body { background: #0f0f23; color: #cccccc; font-family: monospace; margin: 2em; }\
+h1, h2 { color: #00cc00; } h3 { color: #cccccc; } code { color: #ffff66; }\
+section { border-top: 1px solid #333340; padding: 0.5em 0; }\
+table { border-collapse: collapse; margin-bottom: 1em; }\
+th, td { text-align: left; padding: 0.2em 1em 0.2em 0; vertical-align: top; }\
+pre { margin: 0; color: #ffffff; font-weight: bold; }\
+.meta { color: #888888; } .slower { color: #ff6666; } .faster { color: #66ff66; }\
+polyline { fill: none; stroke: #009900; stroke-width: 1.5; stroke-linecap: round; }\
+img { image-rendering: pixelated; max-width: 100%; }";
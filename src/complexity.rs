@@ -0,0 +1,252 @@
+// Fits measured runtimes to the usual complexity classes.
+// Every model is `t = a + b * n + c * f(n)` where `a` absorbs the fixed overhead of a run and `b * n`
+// the work that stays linear (e.g. reading the input), fitted by least squares on the relative error
+// so that every size weighs the same. Coefficients cannot be negative.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Model {
+    Constant,
+    Logarithmic,
+    Linear,
+    Linearithmic,
+    Quadratic,
+    Cubic,
+    // `f(n) = k^n`, `k` is fitted as well
+    Exponential,
+}
+
+impl Model {
+    // From the slowest growing to the fastest
+    pub const ALL: [Model; 7] = [
+        Model::Constant,
+        Model::Logarithmic,
+        Model::Linear,
+        Model::Linearithmic,
+        Model::Quadratic,
+        Model::Cubic,
+        Model::Exponential,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Model::Constant => "O(1)",
+            Model::Logarithmic => "O(log n)",
+            Model::Linear => "O(n)",
+            Model::Linearithmic => "O(n log n)",
+            Model::Quadratic => "O(n²)",
+            Model::Cubic => "O(n³)",
+            Model::Exponential => "O(kⁿ)",
+        }
+    }
+
+    fn growth(self, n: f64) -> f64 {
+        // log n is 0 at n = 1, the models only care about its growth
+        let log_n = n.max(2.0).log2();
+        match self {
+            Model::Constant => 1.0,
+            Model::Logarithmic => log_n,
+            Model::Linear => n,
+            Model::Linearithmic => n * log_n,
+            Model::Quadratic => n * n,
+            Model::Cubic => n * n * n,
+            Model::Exponential => unreachable!("the growth of the exponential model depends on k"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fit {
+    pub model: Model,
+    // `k` of the exponential model
+    pub base: Option<f64>,
+    // Root mean square of the relative errors, 0.1 is a 10% error
+    pub error: f64,
+}
+
+impl std::fmt::Display for Fit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.base {
+            Some(base) => write!(f, "O({:.2}ⁿ)", base)?,
+            None => write!(f, "{}", self.model.name())?,
+        }
+        write!(f, " ({:.1}% error)", self.error * 100.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    // Every model, best fit first
+    pub fits: Vec<Fit>,
+    // `e` of the power law `t = c * n^e` going through the largest half of the sizes
+    pub exponent: f64,
+}
+
+// Faster growing models also fit the measurements of slower ones, they are only picked when their
+// error is below this ratio of the error of the slower ones, plus some measurement noise
+const TOLERANCE: f64 = 1.25;
+const NOISE: f64 = 0.02;
+// Candidates for `k`, from 1.01 to about 4
+const EXPONENTIAL_BASES: i32 = 140;
+
+impl Analysis {
+    // Slowest growing model whose error is close to the lowest one
+    pub fn best(&self) -> &Fit {
+        let lowest = self.fits[0].error;
+        Model::ALL
+            .iter()
+            .filter_map(|model| self.fits.iter().find(|fit| fit.model == *model))
+            .find(|fit| fit.error <= lowest * TOLERANCE + NOISE)
+            .unwrap_or(&self.fits[0])
+    }
+}
+
+// Solves the square system `matrix * x = rhs` by Gaussian elimination, `None` when it is singular
+fn solve_linear(mut matrix: Vec<Vec<f64>>, mut rhs: Vec<f64>) -> Option<Vec<f64>> {
+    let len = rhs.len();
+    let scale = matrix
+        .iter()
+        .flatten()
+        .fold(0.0, |max: f64, value| max.max(value.abs()));
+    for column in 0..len {
+        let pivot = (column..len)
+            .max_by(|&a, &b| matrix[a][column].abs().total_cmp(&matrix[b][column].abs()))?;
+        if matrix[pivot][column].abs() <= 1e-12 * scale {
+            return None;
+        }
+        matrix.swap(column, pivot);
+        rhs.swap(column, pivot);
+        let pivot_row = matrix[column].clone();
+        for row in column + 1..len {
+            let factor = matrix[row][column] / pivot_row[column];
+            for (value, pivot_value) in matrix[row].iter_mut().zip(pivot_row.iter()).skip(column) {
+                *value -= factor * pivot_value;
+            }
+            rhs[row] -= factor * rhs[column];
+        }
+    }
+    let mut solution = vec![0.0; len];
+    for row in (0..len).rev() {
+        let known: f64 = (row + 1..len).map(|i| matrix[row][i] * solution[i]).sum();
+        solution[row] = (rhs[row] - known) / matrix[row][row];
+    }
+    Some(solution)
+}
+
+// Least squares of `t = sum(coefficient * term)` on the relative error, `None` when the terms are
+// not independent or a coefficient is negative. Returns the root mean square of the relative errors
+fn fit_terms(terms: &[&[f64]], times: &[f64]) -> Option<f64> {
+    // dividing every row by `t` turns the relative error into a plain least squares problem
+    let rows: Vec<Vec<f64>> = (0..times.len())
+        .map(|i| terms.iter().map(|term| term[i] / times[i]).collect())
+        .collect();
+    let normal: Vec<Vec<f64>> = (0..terms.len())
+        .map(|a| {
+            (0..terms.len())
+                .map(|b| rows.iter().map(|row| row[a] * row[b]).sum())
+                .collect()
+        })
+        .collect();
+    let rhs: Vec<f64> = (0..terms.len())
+        .map(|a| rows.iter().map(|row| row[a]).sum())
+        .collect();
+    let coefficients = solve_linear(normal, rhs)?;
+    if coefficients.iter().any(|&c| c < 0.0) {
+        return None;
+    }
+    let error = rows
+        .iter()
+        .map(|row| {
+            let predicted: f64 = row
+                .iter()
+                .zip(coefficients.iter())
+                .map(|(x, c)| x * c)
+                .sum();
+            (1.0 - predicted).powi(2)
+        })
+        .sum::<f64>()
+        / times.len() as f64;
+    Some(error.sqrt())
+}
+
+fn fit_model(model: Model, sizes: &[f64], times: &[f64]) -> Fit {
+    let term =
+        |growth: &dyn Fn(f64) -> f64| -> Vec<f64> { sizes.iter().map(|&n| growth(n)).collect() };
+    let overhead = term(&|_| 1.0);
+    let linear = term(&|n| n);
+    let lower_terms: Vec<&[f64]> = match model {
+        Model::Constant => vec![],
+        Model::Logarithmic | Model::Linear => vec![&overhead],
+        _ => vec![&overhead, &linear],
+    };
+    // the growth of the model is always fitted, the lower terms only when they help
+    let fit = |growth: Vec<f64>, base: Option<f64>| {
+        let error = (0..1usize << lower_terms.len())
+            .filter_map(|subset| {
+                let mut terms: Vec<&[f64]> = (0..lower_terms.len())
+                    .filter(|i| subset & (1 << i) != 0)
+                    .map(|i| lower_terms[i])
+                    .collect();
+                terms.push(&growth);
+                fit_terms(&terms, times)
+            })
+            .fold(f64::INFINITY, f64::min);
+        Fit { model, base, error }
+    };
+    match model {
+        Model::Exponential => {
+            let largest = sizes.iter().copied().fold(0.0, f64::max);
+            (1..=EXPONENTIAL_BASES)
+                .map(|i| 1.01f64.powi(i))
+                // k^n is divided by k^largest so that it does not overflow, `c` absorbs the factor
+                .map(|base| fit(term(&|n| base.powf(n - largest)), Some(base)))
+                .min_by(|a, b| a.error.total_cmp(&b.error))
+                .expect("there are candidate bases")
+        }
+        _ => fit(term(&|n| model.growth(n)), None),
+    }
+}
+
+// Slope of the least squares line through the points
+fn slope(points: &[(f64, f64)]) -> f64 {
+    let len = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / len;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / len;
+    let covariance: f64 = points
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    // a single distinct x has no slope
+    if variance > 0.0 {
+        covariance / variance
+    } else {
+        0.0
+    }
+}
+
+// Needs at least 3 measurements `(n, seconds)` with positive times, `None` otherwise
+pub fn analyse(measurements: &[(usize, f64)]) -> Option<Analysis> {
+    let mut points: Vec<(f64, f64)> = measurements
+        .iter()
+        .filter(|(n, seconds)| *n > 0 && *seconds > 0.0)
+        .map(|&(n, seconds)| (n as f64, seconds))
+        .collect();
+    if points.len() < 3 {
+        return None;
+    }
+    points.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let (sizes, times): (Vec<f64>, Vec<f64>) = points.iter().copied().unzip();
+    let mut fits: Vec<Fit> = Model::ALL
+        .iter()
+        .map(|&model| fit_model(model, &sizes, &times))
+        .collect();
+    fits.sort_by(|a, b| a.error.total_cmp(&b.error));
+    let largest: Vec<(f64, f64)> = points[points.len() / 2..]
+        .iter()
+        .map(|(n, t)| (n.ln(), t.ln()))
+        .collect();
+    Some(Analysis {
+        fits,
+        exponent: slope(&largest),
+    })
+}
@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// Shape of the sequence `start, next(start), next(next(start)), ...` once it starts repeating
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleInfo<S> {
+    // Number of states before the first state of the cycle
+    pub tail_length: usize,
+    pub cycle_length: usize,
+    // First state of the sequence that is part of the cycle
+    pub entry: S,
+}
+
+impl<S> CycleInfo<S> {
+    // Smallest step reaching the same state as `step`, e.g. to skip to step 1_000_000_000
+    pub fn reduce(&self, step: usize) -> usize {
+        if step < self.tail_length {
+            return step;
+        }
+        self.tail_length + (step - self.tail_length) % self.cycle_length
+    }
+}
+
+// The detectors below take a `next` function returning `None` once the sequence ends,
+// in which case there is no cycle
+
+// Floyd's tortoise and hare, constant memory
+pub fn floyd<S, F>(start: S, mut next: F) -> Option<CycleInfo<S>>
+where
+    S: Clone + PartialEq,
+    F: FnMut(&S) -> Option<S>,
+{
+    let mut tortoise = next(&start)?;
+    let mut hare = next(&tortoise)?;
+    while tortoise != hare {
+        tortoise = next(&tortoise)?;
+        hare = next(&hare)?;
+        hare = next(&hare)?;
+    }
+    // the distance between them is now a multiple of the cycle length,
+    // moving at the same pace from the start they meet at the entry
+    let mut tail_length = 0;
+    tortoise = start;
+    while tortoise != hare {
+        tortoise = next(&tortoise)?;
+        hare = next(&hare)?;
+        tail_length += 1;
+    }
+    let mut cycle_length = 1;
+    hare = next(&tortoise)?;
+    while tortoise != hare {
+        hare = next(&hare)?;
+        cycle_length += 1;
+    }
+    Some(CycleInfo {
+        tail_length,
+        cycle_length,
+        entry: tortoise,
+    })
+}
+
+// Whether the sequence repeats, the first phase of Brent's algorithm only. Cheaper than `brent`
+// when the shape of the cycle does not matter
+pub fn has_cycle<S, F>(start: S, mut next: F) -> bool
+where
+    S: Clone + PartialEq,
+    F: FnMut(&S) -> Option<S>,
+{
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = start.clone();
+    let Some(mut hare) = next(&start) else {
+        return false;
+    };
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = match next(&hare) {
+            Some(state) => state,
+            None => return false,
+        };
+        length += 1;
+    }
+    true
+}
+
+// Brent's algorithm, constant memory and fewer calls to `next` than Floyd's
+pub fn brent<S, F>(start: S, mut next: F) -> Option<CycleInfo<S>>
+where
+    S: Clone + PartialEq,
+    F: FnMut(&S) -> Option<S>,
+{
+    let mut power = 1;
+    let mut cycle_length = 1;
+    let mut tortoise = start.clone();
+    let mut hare = next(&start)?;
+    while tortoise != hare {
+        if power == cycle_length {
+            tortoise = hare.clone();
+            power *= 2;
+            cycle_length = 0;
+        }
+        hare = next(&hare)?;
+        cycle_length += 1;
+    }
+    // with the hare a cycle length ahead, both meet at the entry
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..cycle_length {
+        hare = next(&hare)?;
+    }
+    let mut tail_length = 0;
+    while tortoise != hare {
+        tortoise = next(&tortoise)?;
+        hare = next(&hare)?;
+        tail_length += 1;
+    }
+    Some(CycleInfo {
+        tail_length,
+        cycle_length,
+        entry: tortoise,
+    })
+}
+
+// Remembers the step of every state, a single pass but memory grows with the sequence
+pub fn hashed<S, F>(start: S, mut next: F) -> Option<CycleInfo<S>>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> Option<S>,
+{
+    let mut steps: HashMap<S, usize> = HashMap::new();
+    let mut state = start;
+    let mut step = 0;
+    loop {
+        if let Some(&first) = steps.get(&state) {
+            return Some(CycleInfo {
+                tail_length: first,
+                cycle_length: step - first,
+                entry: state,
+            });
+        }
+        let next_state = next(&state)?;
+        steps.insert(state, step);
+        state = next_state;
+        step += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 6 -> 3 -> ...: a tail of 3 states, then a cycle of 4 from 3
+    fn rho(state: &usize) -> Option<usize> {
+        Some(if *state == 6 { 3 } else { state + 1 })
+    }
+
+    // 0 -> 1 -> 2 and the sequence ends
+    fn ending(state: &usize) -> Option<usize> {
+        (*state < 2).then_some(state + 1)
+    }
+
+    const RHO: CycleInfo<usize> = CycleInfo {
+        tail_length: 3,
+        cycle_length: 4,
+        entry: 3,
+    };
+
+    #[test]
+    fn detectors_find_tail_and_cycle() {
+        assert_eq!(floyd(0, rho), Some(RHO));
+        assert_eq!(brent(0, rho), Some(RHO));
+        assert_eq!(hashed(0, rho), Some(RHO));
+    }
+
+    #[test]
+    fn start_inside_the_cycle_has_no_tail() {
+        let info = CycleInfo {
+            tail_length: 0,
+            cycle_length: 4,
+            entry: 5,
+        };
+        assert_eq!(floyd(5, rho), Some(info.clone()));
+        assert_eq!(brent(5, rho), Some(info.clone()));
+        assert_eq!(hashed(5, rho), Some(info));
+    }
+
+    #[test]
+    fn fixed_point_is_a_cycle_of_one() {
+        let info = CycleInfo {
+            tail_length: 2,
+            cycle_length: 1,
+            entry: 2,
+        };
+        let next = |state: &usize| Some((*state + 1).min(2));
+        assert_eq!(floyd(0, next), Some(info.clone()));
+        assert_eq!(brent(0, next), Some(info.clone()));
+        assert_eq!(hashed(0, next), Some(info));
+        assert!(has_cycle(0, next));
+    }
+
+    #[test]
+    fn ending_sequence_has_no_cycle() {
+        assert_eq!(floyd(0, ending), None);
+        assert_eq!(brent(0, ending), None);
+        assert_eq!(hashed(0, ending), None);
+        assert!(!has_cycle(0, ending));
+        assert!(!has_cycle(2, ending));
+    }
+
+    #[test]
+    fn has_cycle_agrees_with_brent() {
+        for start in 0..7 {
+            assert_eq!(has_cycle(start, rho), brent(start, rho).is_some());
+            assert_eq!(has_cycle(start, ending), brent(start, ending).is_some());
+        }
+    }
+
+    #[test]
+    fn reduce_skips_whole_cycles() {
+        assert_eq!(RHO.reduce(2), 2);
+        assert_eq!(RHO.reduce(3), 3);
+        assert_eq!(RHO.reduce(7), 3);
+        assert_eq!(RHO.reduce(1_000_000_000), 3 + (1_000_000_000 - 3) % 4);
+    }
+}
@@ -1,44 +1,35 @@
-use anyhow::{Context, Result};
+use crate::error::{Error, Result};
+use crate::generate::{Generator, Rng};
+use crate::input::LineReader;
+use crate::report::Report;
+use crate::solution::{self, Solution};
 use std::collections::HashMap;
-use std::fs;
-use std::path::PathBuf;
+use std::path::Path;
 
-fn read_input(file_path: &PathBuf) -> Result<(Vec<i32>, Vec<i32>, HashMap<i32, i32>)> {
-    let input = fs::read_to_string(file_path).context("Failed to read input file")?;
+type LocationLists = (Vec<i32>, Vec<i32>, HashMap<i32, i32>);
+
+fn read_input(file_path: &Path) -> Result<LocationLists> {
+    let mut reader = LineReader::open(file_path)?;
     let mut left_column: Vec<i32> = Vec::new();
     let mut right_column: Vec<i32> = Vec::new();
     let mut right_location_id_map: HashMap<i32, i32> = HashMap::new();
 
-    for line in input.lines() {
-        let mut parts = line.split_whitespace();
-        let left: i32 = parts
-            .next()
-            .context("Failed to get left column")?
-            .parse()
-            .context(format!("Failed to parse left column for line: {}", line))?;
-        let right: i32 = parts
-            .next()
-            .context("Failed to get right column")?
-            .parse()
-            .context(format!("Failed to parse right column for line: {}", line))?;
+    while let Some(line) = reader.next_content_line()? {
+        let values: Vec<i32> = line.exact_columns(2)?;
+        let (left, right) = (values[0], values[1]);
         left_column.push(left);
         right_column.push(right);
-        match right_location_id_map.get_mut(&right) {
-            Some(val) => *val += 1,
-            None => {
-                right_location_id_map.insert(right, 1);
-            }
-        }
+        *right_location_id_map.entry(right).or_insert(0) += 1;
     }
     left_column.sort_unstable();
     right_column.sort_unstable();
     if left_column.len() != right_column.len() {
-        return Err(anyhow::anyhow!(format!("left and right column must have the same length, left column has {} elements and right column has {} elements", left_column.len(), right_column.len())));
+        return Err(Error::InvalidInput(format!("left and right column must have the same length, left column has {} elements and right column has {} elements", left_column.len(), right_column.len())));
     }
     Ok((left_column, right_column, right_location_id_map))
 }
 
-fn get_sum_diff(left_column: &[i32], right_column: &[i32]) {
+fn get_sum_diff(left_column: &[i32], right_column: &[i32]) -> i32 {
     let mut sum_diff = 0;
     left_column
         .iter()
@@ -46,23 +37,65 @@ fn get_sum_diff(left_column: &[i32], right_column: &[i32]) {
         .for_each(|(a, b)| {
             sum_diff += a.max(b) - a.min(b);
         });
-    println!("Sum of location id differences: {}", sum_diff);
+    sum_diff
 }
 
-fn compute_similarity(left_column: Vec<i32>, right_location_id_map: HashMap<i32, i32>) -> () {
+fn compute_similarity(left_column: &[i32], right_location_id_map: &HashMap<i32, i32>) -> i32 {
     let mut similarity_score = 0;
     for left in left_column {
-        let right_count = right_location_id_map.get(&left).unwrap_or(&0);
+        let right_count = right_location_id_map.get(left).unwrap_or(&0);
         similarity_score += left * right_count;
     }
-    println!("Similarity score: {}", similarity_score);
+    similarity_score
+}
+
+// `size` lines, left ids are odd and right ids are even except for a few matching ones,
+// ids are kept small so that the answers fit in an i32
+fn generate(size: usize, rng: &mut Rng) -> String {
+    let mut input = String::new();
+    for _ in 0..size {
+        let left = 2 * rng.range(0, 499) + 1;
+        let right = if rng.chance(100.0 / size as f64) {
+            2 * rng.range(0, 499) + 1
+        } else {
+            2 * rng.range(1, 500)
+        };
+        input.push_str(&format!("{}   {}\n", left, right));
+    }
+    input
+}
+
+pub const GENERATOR: Generator = Generator {
+    generate,
+    size_unit: "lines",
+    min_size: 1000,
+    growth: 2.0,
+};
+
+struct Day;
+
+impl Solution for Day {
+    type Input = LocationLists;
+    type Artifacts = ();
+    type Part1 = i32;
+    type Part2 = i32;
+
+    const PART_1: &'static str = "Sum of location id differences";
+    const PART_2: &'static str = "Similarity score";
+
+    fn parse(file_path: &Path) -> Result<Self::Input> {
+        read_input(file_path)
+    }
+
+    fn part_1((left_column, right_column, _): &Self::Input) -> Result<(i32, ())> {
+        Ok((get_sum_diff(left_column, right_column), ()))
+    }
+
+    fn part_2((left_column, _, right_location_id_map): &Self::Input, _: ()) -> Result<i32> {
+        Ok(compute_similarity(left_column, right_location_id_map))
+    }
 }
 
-pub fn solve(file_path: &PathBuf) -> Result<()> {
-    let (left_column, right_column, right_location_id_map) = read_input(file_path)?;
-    // Part 1
-    get_sum_diff(&left_column, &right_column);
-    // Part 2
-    compute_similarity(left_column, right_location_id_map);
-    Ok(())
+pub fn solve(file_path: &Path) -> Result<Report> {
+    solution::run::<Day>(file_path)
 }
@@ -1,14 +1,9 @@
-use anyhow::{Context, Result};
-use std::fs;
-use std::path::PathBuf;
-
-fn parse_level(level: &str) -> Result<i32> {
-    let parsed_level: i32 = level.parse().context(format!(
-        "level value `{}` could not be parsed into an i32",
-        level
-    ))?;
-    Ok(parsed_level)
-}
+use crate::error::Result;
+use crate::generate::{Generator, Rng};
+use crate::input::LineReader;
+use crate::report::Report;
+use crate::solution::{self, Solution};
+use std::path::Path;
 
 fn get_factor(prev: i32, next: i32) -> Option<i32> {
     (prev - next).checked_div((prev - next).abs())
@@ -28,57 +23,39 @@ fn report_is_valid(levels: &[i32]) -> bool {
         })
 }
 
-fn validate_reports(file_path: &PathBuf) -> Result<i32> {
-    let input = fs::read_to_string(file_path).context("Failed to read input file")?;
+fn read_input(file_path: &Path) -> Result<Vec<Vec<i32>>> {
+    let mut reader = LineReader::open(file_path)?;
+    let mut reports = Vec::new();
+    while let Some(line) = reader.next_content_line()? {
+        reports.push(line.columns()?);
+    }
+    Ok(reports)
+}
+
+// Returns the number of valid reports and the index of the others
+fn validate_reports(reports: &[Vec<i32>]) -> (i32, Vec<usize>) {
     let mut correct_report_count = 0;
-    for line in input.lines() {
-        let levels: Vec<&str> = line.split_whitespace().collect();
-        let levels: Vec<i32> = levels
-            .iter()
-            .map(|level| parse_level(level))
-            .collect::<Result<Vec<i32>>>()?;
-        if levels.len() < 2 {
+    let mut invalid_reports = Vec::new();
+    for (i, levels) in reports.iter().enumerate() {
+        if levels.len() < 2 || report_is_valid(levels) {
             correct_report_count += 1;
-            continue;
-        }
-        let factor = get_factor(levels[0], levels[1]);
-        if factor.is_none() {
-            continue;
-        }
-        let factor = factor.unwrap();
-        let valid_report = levels
-            .iter()
-            .zip(levels.iter().skip(1))
-            .all(|(prev, next)| {
-                let diff = factor * (prev - next);
-                0 < diff && diff < 4
-            });
-        if valid_report {
-            correct_report_count += 1
+        } else {
+            invalid_reports.push(i);
         }
     }
-    Ok(correct_report_count)
+    (correct_report_count, invalid_reports)
 }
 
 // Yes this is O(n^2) ... Sue me...
-fn validate_reports_with_problem_dapener(file_path: &PathBuf) -> Result<i32> {
-    let input = fs::read_to_string(file_path).context("Failed to read input file")?;
-    let mut correct_report_count = 0;
-    for line in input.lines() {
-        let levels: Vec<&str> = line.split_whitespace().collect();
-        let levels: Vec<i32> = levels
-            .iter()
-            .map(|level| parse_level(level))
-            .collect::<Result<Vec<i32>>>()?;
+// Valid reports stay valid, only the ones part 1 rejected need the dampener
+fn validate_reports_with_problem_dapener(reports: &[Vec<i32>], invalid_reports: &[usize]) -> i32 {
+    let mut correct_report_count = (reports.len() - invalid_reports.len()) as i32;
+    for &report in invalid_reports {
+        let levels = &reports[report];
         if levels.len() < 3 {
             correct_report_count += 1;
             continue;
         }
-
-        if report_is_valid(&levels) {
-            correct_report_count += 1;
-            continue;
-        }
         for i in 0..levels.len() {
             let mut lev = levels.clone();
             lev.remove(i);
@@ -88,17 +65,69 @@ fn validate_reports_with_problem_dapener(file_path: &PathBuf) -> Result<i32> {
             }
         }
     }
-    Ok(correct_report_count)
+    correct_report_count
 }
 
-pub fn solve(file_path: &PathBuf) -> Result<()> {
-    let valid_report_count = validate_reports(file_path)?;
-    println!("Number of valid report is: {}", valid_report_count);
+const GENERATED_REPORTS: usize = 200;
+
+// Reports of `size` levels, a third are valid, a third have one bad level and the rest have two
+fn generate(size: usize, rng: &mut Rng) -> String {
+    let mut input = String::new();
+    for _ in 0..GENERATED_REPORTS {
+        let step = if rng.chance(0.5) { 1 } else { -1 };
+        let mut level = rng.range(10, 99) + 3 * size as i64;
+        let mut levels: Vec<i64> = (0..size)
+            .map(|_| {
+                level += step * rng.range(1, 3);
+                level
+            })
+            .collect();
+        for _ in 0..rng.below(3) {
+            let i = rng.below(size);
+            levels[i] += step * rng.range(4, 9);
+        }
+        let line: Vec<String> = levels.iter().map(|level| level.to_string()).collect();
+        input.push_str(&line.join(" "));
+        input.push('\n');
+    }
+    input
+}
+
+pub const GENERATOR: Generator = Generator {
+    generate,
+    size_unit: "levels per report",
+    min_size: 8,
+    growth: 2.0,
+};
+
+struct Day;
+
+impl Solution for Day {
+    type Input = Vec<Vec<i32>>;
+    // Index of the reports that are not valid without the dampener
+    type Artifacts = Vec<usize>;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    const PART_1: &'static str = "Number of valid report";
+    const PART_2: &'static str = "Number of valid report when using Problem Dampener methodology";
+
+    fn parse(file_path: &Path) -> Result<Self::Input> {
+        read_input(file_path)
+    }
+
+    fn part_1(reports: &Self::Input) -> Result<(i32, Vec<usize>)> {
+        Ok(validate_reports(reports))
+    }
+
+    fn part_2(reports: &Self::Input, invalid_reports: Vec<usize>) -> Result<i32> {
+        Ok(validate_reports_with_problem_dapener(
+            reports,
+            &invalid_reports,
+        ))
+    }
+}
 
-    let valid_report_by_problem_dampener = validate_reports_with_problem_dapener(file_path)?;
-    println!(
-        "Number of valid report when using Problem Dampener methodology is: {}",
-        valid_report_by_problem_dampener
-    );
-    Ok(())
+pub fn solve(file_path: &Path) -> Result<Report> {
+    solution::run::<Day>(file_path)
 }
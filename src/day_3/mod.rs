@@ -1,83 +1,129 @@
-use anyhow::{anyhow, Context, Result};
+use crate::error::{Error, Result};
+use crate::generate::{Generator, Rng};
+use crate::parse::{Line, Source};
+use crate::report::Report;
+use crate::solution::{self, Solution};
 use regex::Regex;
-use std::fs;
-use std::path::PathBuf;
+use std::path::Path;
 
-fn perform_multiplication(mul: String) -> Result<i32> {
-    let parts: Vec<&str> = mul.split("(").collect();
-    if parts.len() != 2 {
-        return Err(anyhow!("Unexpected multiplication format for `{}`", mul));
-    }
-    let parts: Vec<&str> = parts[1].split(")").collect();
-    if parts.len() != 2 {
-        return Err(anyhow!("Unexpected multiplication format for `{}`", mul));
-    }
-    let parts: Vec<&str> = parts[0].split(",").collect();
-    let first_num: i32 = parts[0]
-        .parse()
-        .context("Unexpected multiplication format for `{}`, during number parsing")?;
-    let sec_num: i32 = parts[1]
-        .parse()
-        .context("Unexpected multiplication format for `{}`, during number parsing")?;
+// `mul` is the text of an instruction found on `line`, errors point at it
+fn perform_multiplication(line: &Line, mul: &str) -> Result<i32> {
+    let format_error = || {
+        line.error_at_token(
+            mul,
+            format!("Unexpected multiplication format for `{}`", mul),
+        )
+    };
+    let operands = mul
+        .strip_prefix("mul(")
+        .and_then(|rest| rest.strip_suffix(')'))
+        .ok_or_else(format_error)?;
+    let (first, second) = operands.split_once(',').ok_or_else(format_error)?;
+    let first_num: i32 = line.parse_token(first)?;
+    let sec_num: i32 = line.parse_token(second)?;
     Ok(first_num * sec_num)
 }
 
 // Part 1
-fn sum_sans_control_flow(input: &str) -> Result<i32> {
-    let re = Regex::new(r"mul\(\d{1,3}\,\d{1,3}\)").context("Failed to generate regex")?;
+fn sum_sans_control_flow(input: &Source) -> Result<i32> {
+    let re = Regex::new(r"mul\(\d{1,3}\,\d{1,3}\)")
+        .map_err(|err| Error::Internal(format!("Failed to generate regex: {}", err)))?;
     let mut total = 0;
-    let results: Vec<String> = re
-        .find_iter(input)
-        .map(|mat| mat.as_str().to_string())
-        .collect();
-
-    for r in results {
-        total += perform_multiplication(r)?;
+    // instructions never span lines, matching line by line locates them
+    for line in input.lines() {
+        for mat in re.find_iter(line.text) {
+            total += perform_multiplication(&line, mat.as_str())?;
+        }
     }
     Ok(total)
 }
 
 // Part 2
-fn sum_with_control_flow(input: &str) -> Result<i32> {
-    let re = Regex::new(r"mul\(\d{1,3},\d{1,3}\)|don't|do").context("Failed to generate regex")?;
+fn sum_with_control_flow(input: &Source) -> Result<i32> {
+    let re = Regex::new(r"mul\(\d{1,3},\d{1,3}\)|don't|do")
+        .map_err(|err| Error::Internal(format!("Failed to generate regex: {}", err)))?;
     let mut total = 0;
-    let results: Vec<String> = re
-        .find_iter(input)
-        .map(|mat| mat.as_str().to_string())
-        .collect();
     let mut on_flag = true;
-    for r in results {
-        if r == "do" {
-            on_flag = true;
-            continue;
+    for line in input.lines() {
+        for mat in re.find_iter(line.text) {
+            let r = mat.as_str();
+            if r == "do" {
+                on_flag = true;
+                continue;
+            }
+            if r == "don't" {
+                on_flag = false;
+                continue;
+            }
+            if on_flag {
+                total += perform_multiplication(&line, r)?;
+            }
         }
-        if r == "don't" {
-            on_flag = false;
-            continue;
+    }
+    Ok(total)
+}
+
+const GARBAGE: [&str; 8] = [
+    "#",
+    "from()",
+    "what()",
+    "mul[3,7]",
+    "mul(4*",
+    "]%",
+    "?mul (2,3)",
+    "@",
+];
+
+// `size` instructions surrounded by garbage, operands are kept small so that the sums fit in an i32
+fn generate(size: usize, rng: &mut Rng) -> String {
+    let mut input = String::new();
+    for i in 0..size {
+        match rng.below(10) {
+            0 => input.push_str("do()"),
+            1 => input.push_str("don't()"),
+            _ => input.push_str(&format!("mul({},{})", rng.range(1, 20), rng.range(1, 20))),
         }
-        if on_flag {
-            total += perform_multiplication(r)?;
+        input.push_str(GARBAGE[rng.below(GARBAGE.len())]);
+        if i % 20 == 19 {
+            input.push('\n');
         }
     }
-    Ok(total)
+    input
 }
 
-pub fn solve(file_path: &PathBuf) -> Result<()> {
-    let input = fs::read_to_string(file_path).context("Failed to read input file")?;
-    // let input = "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))mul ( 2 , 4 )";
-    // let input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+pub const GENERATOR: Generator = Generator {
+    generate,
+    size_unit: "instructions",
+    min_size: 1000,
+    growth: 2.0,
+};
+
+struct Day;
+
+impl Solution for Day {
+    type Input = Source;
+    type Artifacts = ();
+    type Part1 = i32;
+    type Part2 = i32;
 
-    let sans_control_flow_total = sum_sans_control_flow(&input)?;
+    const PART_1: &'static str = "multiplication sum (Sans Control Flow)";
+    const PART_2: &'static str = "multiplication sum (With Control Flow)";
+
+    fn parse(file_path: &Path) -> Result<Self::Input> {
+        // let input = "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))mul ( 2 , 4 )";
+        // let input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        Source::read(file_path)
+    }
 
-    println!(
-        "multiplication sum (Sans Control Flow): {}",
-        sans_control_flow_total
-    );
+    fn part_1(input: &Self::Input) -> Result<(i32, ())> {
+        Ok((sum_sans_control_flow(input)?, ()))
+    }
+
+    fn part_2(input: &Self::Input, _: ()) -> Result<i32> {
+        sum_with_control_flow(input)
+    }
+}
 
-    let with_control_flow_total = sum_with_control_flow(&input)?;
-    println!(
-        "multiplication sum (With Control Flow): {}",
-        with_control_flow_total
-    );
-    Ok(())
+pub fn solve(file_path: &Path) -> Result<Report> {
+    solution::run::<Day>(file_path)
 }
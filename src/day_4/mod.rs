@@ -1,136 +1,33 @@
-use anyhow::{anyhow, Context, Result};
-use std::fs;
-use std::path::PathBuf;
+use crate::error::{Error, Result};
+use crate::generate::{Generator, Rng};
+use crate::grid::Grid;
+use crate::parse::Source;
+use crate::point::{Direction, Point};
+use crate::render::{Canvas, Color, Style};
+use crate::report::Report;
+use crate::solution::{self, Solution};
+use std::path::Path;
 
 // Read text into a 2D matrix
-fn read_input(file_path: &PathBuf) -> Result<Vec<Vec<char>>> {
-    let input = fs::read_to_string(file_path).context("Failed to read input file")?;
-    let mut rv: Vec<Vec<char>> = Vec::new();
-    for line in input.lines() {
-        if line.len() == 0 {
-            continue;
-        }
-        let mut line_vec = Vec::new();
-        for char in line.chars() {
-            line_vec.push(char);
-        }
-        // Let's verify that the matrix is a rectangle
-        if rv.len() > 0 && rv[0].len() != line_vec.len() {
-            return Err(anyhow!(
-                "row does not have the expected number of letters, expected {}, got {}",
-                rv.len(),
-                line_vec.len()
-            ));
-        }
-        rv.push(line_vec);
-    }
-    Ok(rv)
-}
-
-struct Coordinate {
-    row: usize,
-    column: usize,
-}
-
-impl Coordinate {
-    fn new(row: usize, col: usize) -> Self {
-        Self {
-            row: row,
-            column: col,
-        }
-    }
-}
-
-struct PathLine {
-    coordinates: Vec<Coordinate>,
-}
-
-impl PathLine {
-    fn skip(&self, word_len: usize) -> bool {
-        self.coordinates.len() < word_len
-    }
-}
-
-fn generate_line_paths(rows: usize, columns: usize) -> Vec<PathLine> {
-    let mut paths: Vec<PathLine> = Vec::new();
-
-    // Horizontal traversal
-    for row in 0..rows {
-        let mut row_coords: Vec<Coordinate> = Vec::new();
-        for column in 0..columns {
-            row_coords.push(Coordinate::new(row, column));
-        }
-        paths.push(PathLine {
-            coordinates: row_coords,
-        });
+fn read_input(file_path: &Path) -> Result<Grid<char>> {
+    let matrix = Source::read(file_path)?.char_grid()?;
+    if matrix.is_empty() {
+        return Err(Error::InvalidInput("letter matrix is empty".to_string()));
     }
-
-    // Vertical traversal
-    for column in 0..columns {
-        let mut col_coords: Vec<Coordinate> = Vec::new();
-        for row in 0..rows {
-            col_coords.push(Coordinate::new(row, column));
-        }
-        paths.push(PathLine {
-            coordinates: col_coords,
-        });
-    }
-
-    // TODO:
-    // We should unify the loops for both diagonals
-    // Traverse diagonal from top-left to bottom-right
-    for d in 0..(rows + columns - 1) {
-        let mut diag_coords: Vec<Coordinate> = Vec::new();
-        for i in 0..=d {
-            let row = i;
-            let col = d - i;
-            if row < rows && col < columns {
-                diag_coords.push(Coordinate::new(row, col));
-            }
-        }
-        if !diag_coords.is_empty() {
-            paths.push(PathLine {
-                coordinates: diag_coords,
-            });
-        }
-    }
-
-    // Traverse diagonal from top-right to bottom-left
-    for d in 0..(rows + columns - 1) {
-        let mut diag_coords: Vec<Coordinate> = Vec::new();
-        for i in 0..=d {
-            let row = i;
-            if d - i < columns {
-                let col = columns - 1 - (d - i);
-                if row < rows {
-                    diag_coords.push(Coordinate::new(row, col));
-                }
-            }
-        }
-        if !diag_coords.is_empty() {
-            paths.push(PathLine {
-                coordinates: diag_coords,
-            });
-        }
-    }
-    paths
+    Ok(matrix)
 }
 
-fn find_word_count_in_path(
-    matrix: &Vec<Vec<char>>,
-    coordinates: &[&Coordinate],
-    word_chars: &[char],
-) -> i32 {
+fn find_word_count_in_path(path: &[char], word_chars: &[char]) -> i32 {
     let mut total = 0;
     let mut agg: Vec<char> = Vec::new();
     let mut word_index: usize = 0;
 
-    for coord in coordinates {
-        if matrix[coord.row][coord.column] == word_chars[word_index] {
-            agg.push(matrix[coord.row][coord.column]);
+    for &letter in path {
+        if letter == word_chars[word_index] {
+            agg.push(letter);
             word_index += 1;
-        } else if matrix[coord.row][coord.column] == word_chars[0] {
-            agg = vec![matrix[coord.row][coord.column]];
+        } else if letter == word_chars[0] {
+            agg = vec![letter];
             word_index = 1;
         } else {
             agg = Vec::new();
@@ -146,90 +43,215 @@ fn find_word_count_in_path(
     total
 }
 
-fn find_word_count_in_matrix(matrix: &Vec<Vec<char>>, paths: Vec<PathLine>, word: &str) -> i32 {
+// Counts the word forward and backward along a row, column or diagonal of the matrix
+fn find_word_count_in_line<I: Iterator<Item = (usize, usize)>>(
+    matrix: &Grid<char>,
+    positions: I,
+    path: &mut Vec<char>,
+    word_chars: &[char],
+    word_chars_reverse: &[char],
+) -> i32 {
+    path.clear();
+    path.extend(positions.map(|position| matrix[position]));
+    if path.len() < word_chars.len() {
+        return 0;
+    }
+    // we are doing the path forward and backward but technically we could check for the word backward
+    // inside the find_word_count_in_path function
+    find_word_count_in_path(path, word_chars) + find_word_count_in_path(path, word_chars_reverse)
+}
+
+fn find_word_count_in_matrix(matrix: &Grid<char>, word: &str) -> i32 {
     let mut total = 0;
     let word_chars: Vec<char> = word.chars().collect();
     let word_chars_reverse = word_chars.iter().rev().copied().collect::<Vec<_>>();
-    for path in paths {
-        if path.skip(word_chars.len()) {
-            continue;
-        }
-        let coords: Vec<&Coordinate> = path.coordinates.iter().collect();
-        // we are doing the path forward and backward but technically we could check for the word backward
-        // inside the find_word_count_in_path function
-        total += find_word_count_in_path(matrix, &coords, &word_chars);
-        total += find_word_count_in_path(matrix, &coords, &word_chars_reverse);
+    // letters of the line being checked, reused across lines
+    let mut path: Vec<char> = Vec::new();
+
+    for row in 0..matrix.rows() {
+        let positions = matrix.row_positions(row);
+        total += find_word_count_in_line(
+            matrix,
+            positions,
+            &mut path,
+            &word_chars,
+            &word_chars_reverse,
+        );
+    }
+    for column in 0..matrix.columns() {
+        let positions = matrix.column_positions(column);
+        total += find_word_count_in_line(
+            matrix,
+            positions,
+            &mut path,
+            &word_chars,
+            &word_chars_reverse,
+        );
+    }
+    for diagonal in 0..matrix.diagonal_count() {
+        let positions = matrix.diagonal_positions(diagonal);
+        total += find_word_count_in_line(
+            matrix,
+            positions,
+            &mut path,
+            &word_chars,
+            &word_chars_reverse,
+        );
+        let positions = matrix.anti_diagonal_positions(diagonal);
+        total += find_word_count_in_line(
+            matrix,
+            positions,
+            &mut path,
+            &word_chars,
+            &word_chars_reverse,
+        );
     }
     total
 }
 
-fn check_x_word_at_position(
-    matrix: &Vec<Vec<char>>,
-    row: usize,
-    col: usize,
+// Whether the word is spelled from `start` toward `direction`, letters outside of the matrix never match
+fn is_word_at(
+    matrix: &Grid<char>,
+    start: Point,
+    direction: Direction,
     word_chars: &[char],
-) -> i32 {
+) -> bool {
+    word_chars
+        .iter()
+        .enumerate()
+        .all(|(i, letter)| matrix.get_point(start + direction.offset() * i as i64) == Some(letter))
+}
+
+// `corner` is the top left corner of the square containing the X
+fn check_x_word_at_position(matrix: &Grid<char>, corner: Point, word_chars: &[char]) -> i32 {
+    let last = word_chars.len() as i64 - 1;
     // Check top left to bottom right diagonal
     // If false we can early exit
-    if !(0..word_chars.len())
-        .map(|i| word_chars[i] == matrix[row + i][col + i])
-        .all(|x| x)
-    {
+    if !is_word_at(matrix, corner, Direction::DownRight, word_chars) {
         return 0;
     }
 
     // Check top right to bottom left diagonal
     // If true we have found the word in the two diagonals that come from the top corners
-    if (0..word_chars.len())
-        .map(|i| word_chars[i] == matrix[row + i][col + word_chars.len() - 1 - i])
-        .all(|x| x)
-    {
+    let top_right = corner + Direction::Right.offset() * last;
+    if is_word_at(matrix, top_right, Direction::DownLeft, word_chars) {
         return 1;
     }
 
     // Check bottom left to top right diagonal
     // If true we have found the word in the two diagonals that come from the left corners
-    if (0..word_chars.len())
-        .map(|i| word_chars[i] == matrix[row + word_chars.len() - 1 - i][col + i])
-        .all(|x| x)
-    {
+    let bottom_left = corner + Direction::Down.offset() * last;
+    if is_word_at(matrix, bottom_left, Direction::UpRight, word_chars) {
         return 1;
     }
     0
 }
 
-fn find_x_word_count_in_matrix(matrix: &Vec<Vec<char>>, word: &str) -> i32 {
-    let matrix_row_bound = matrix.len();
-    let matrix_col_bound = matrix[0].len();
-
+fn find_x_word_count_in_matrix(matrix: &Grid<char>, word: &str) -> i32 {
     let mut total = 0;
     let word_chars: Vec<char> = word.chars().collect();
     let word_chars_reverse = word_chars.iter().rev().copied().collect::<Vec<_>>();
 
-    // we stop when we are on a row that is too close to the edge and cannot fit the word
-    for row in 0..matrix_row_bound - word_chars.len() + 1 {
-        // we stop when we are on a column that is too close to the edge and cannot fit the word
-        for col in 0..matrix_col_bound - word_chars.len() + 1 {
-            total += check_x_word_at_position(matrix, row, col, &word_chars);
-            // handling diagonal from bottom corners and right corners by checking reversed word
-            total += check_x_word_at_position(matrix, row, col, &word_chars_reverse);
-        }
+    // squares that do not fit in the matrix are rejected by the bounds checks of `is_word_at`
+    for (position, _) in matrix.iter() {
+        let corner = Point::from_position(position);
+        total += check_x_word_at_position(matrix, corner, &word_chars);
+        // handling diagonal from bottom corners and right corners by checking reversed word
+        total += check_x_word_at_position(matrix, corner, &word_chars_reverse);
     }
     total
 }
 
-pub fn solve(file_path: &PathBuf) -> Result<()> {
-    let input_matrix = read_input(file_path)?;
-    let paths = generate_line_paths(input_matrix.len(), input_matrix[0].len());
-    let word = "XMAS";
-    let word_count = find_word_count_in_matrix(&input_matrix, paths, word);
-    println!("The word count for the word `{}` is {}", word, word_count);
-
-    let word: &str = "MAS";
-    let x_word_count = find_x_word_count_in_matrix(&input_matrix, word);
-    println!(
-        "The X- word count for the word `{}` is {}",
-        word, x_word_count
-    );
-    Ok(())
+// Cells of every match of the word, in all 8 directions
+fn word_match_cells(matrix: &Grid<char>, word: &str) -> Vec<Point> {
+    let word_chars: Vec<char> = word.chars().collect();
+    let mut cells = Vec::new();
+    for (position, _) in matrix.iter() {
+        let start = Point::from_position(position);
+        for direction in Direction::ALL {
+            if is_word_at(matrix, start, direction, &word_chars) {
+                cells.extend((0..word_chars.len()).map(|i| start + direction.offset() * i as i64));
+            }
+        }
+    }
+    cells
+}
+
+// Cells of the two diagonals of every X made of the word
+fn x_word_match_cells(matrix: &Grid<char>, word: &str) -> Vec<Point> {
+    let word_chars: Vec<char> = word.chars().collect();
+    let word_chars_reverse = word_chars.iter().rev().copied().collect::<Vec<_>>();
+    let last = word_chars.len() as i64 - 1;
+    let mut cells = Vec::new();
+    for (position, _) in matrix.iter() {
+        let corner = Point::from_position(position);
+        if check_x_word_at_position(matrix, corner, &word_chars) == 0
+            && check_x_word_at_position(matrix, corner, &word_chars_reverse) == 0
+        {
+            continue;
+        }
+        for i in 0..=last {
+            cells.push(corner + Point::new(i, i));
+            cells.push(corner + Point::new(i, last - i));
+        }
+    }
+    cells
+}
+
+pub fn render(file_path: &Path) -> Result<Canvas> {
+    let matrix = read_input(file_path)?;
+    let mut canvas = Canvas::new(matrix.clone());
+    canvas
+        .add_layer("XMAS", Style::fg(Color::Green).bold())
+        .extend(word_match_cells(&matrix, "XMAS"));
+    canvas
+        .add_layer("X-MAS", Style::bg(Color::Blue))
+        .extend(x_word_match_cells(&matrix, "MAS"));
+    Ok(canvas)
+}
+
+// Square grid of random XMAS letters, `size` is its side
+fn generate(size: usize, rng: &mut Rng) -> String {
+    let letters = ['X', 'M', 'A', 'S'];
+    let mut input = String::new();
+    for _ in 0..size {
+        input.extend((0..size).map(|_| *rng.pick(&letters)));
+        input.push('\n');
+    }
+    input
+}
+
+pub const GENERATOR: Generator = Generator {
+    generate,
+    size_unit: "grid side",
+    min_size: 32,
+    growth: 2.0,
+};
+
+struct Day;
+
+impl Solution for Day {
+    type Input = Grid<char>;
+    type Artifacts = ();
+    type Part1 = i32;
+    type Part2 = i32;
+
+    const PART_1: &'static str = "The word count for the word `XMAS`";
+    const PART_2: &'static str = "The X- word count for the word `MAS`";
+
+    fn parse(file_path: &Path) -> Result<Self::Input> {
+        read_input(file_path)
+    }
+
+    fn part_1(input_matrix: &Self::Input) -> Result<(i32, ())> {
+        Ok((find_word_count_in_matrix(input_matrix, "XMAS"), ()))
+    }
+
+    fn part_2(input_matrix: &Self::Input, _: ()) -> Result<i32> {
+        Ok(find_x_word_count_in_matrix(input_matrix, "MAS"))
+    }
+}
+
+pub fn solve(file_path: &Path) -> Result<Report> {
+    solution::run::<Day>(file_path)
 }
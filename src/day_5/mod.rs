@@ -1,138 +1,158 @@
-use anyhow::{Context, Result};
-use std::cmp::Ordering;
-use std::path::PathBuf;
-use std::{
-    collections::{HashMap, HashSet},
-    fs,
-};
+use crate::cache;
+use crate::error::{Error, Result};
+use crate::generate::{Generator, Rng};
+use crate::graph::Graph;
+use crate::parse::{ParseResult, Source};
+use crate::report::Report;
+use crate::solution::{self, Solution};
+use std::path::Path;
+
+// An edge `before -> after` for every `before|after` rule
+type Rules = Graph<i32>;
 
-fn convert_str_to_i32(values: Vec<&str>) -> Result<Vec<i32>> {
-    values
+// The input is made of a section of `before|after` rules followed by a section of sequences
+fn read_input(file_path: &Path) -> Result<(Rules, Vec<Vec<i32>>)> {
+    let source = Source::read(file_path)?;
+    let sections = source.sections();
+    if sections.len() != 2 {
+        return Err(Error::InvalidInput(format!(
+            "expected a section of rules and a section of sequences, got {} sections",
+            sections.len()
+        )));
+    }
+    let mut rules: Rules = Graph::new();
+    for line in sections[0].iter() {
+        let values: Vec<i32> = line.separated('|')?;
+        if values.len() != 2 {
+            return Err(line
+                .error("rules must be formatted as `before|after`".to_string())
+                .into());
+        }
+        rules.add_edge(values[0], values[1]);
+    }
+    let sequences = sections[1]
         .iter()
-        .map(|v| {
-            v.parse::<i32>()
-                .context(format!("Failed to parse value: {}", v))
+        .map(|line| line.separated(','))
+        .collect::<ParseResult<Vec<Vec<i32>>>>()?;
+    if let Some(line) = sections[1]
+        .iter()
+        .zip(sequences.iter())
+        .find(|(_, s)| s.is_empty())
+    {
+        return Err(line.0.error("empty sequence".to_string()).into());
+    }
+    Ok((rules, sequences))
+}
+
+// A sequence is valid when no page has a rule asking it to come before an earlier page
+fn is_valid_sequence(sequence: &[i32], rules: &Rules) -> bool {
+    sequence.iter().enumerate().all(|(i, item)| {
+        sequence[..i]
+            .iter()
+            .all(|earlier| !rules.contains_edge(item, earlier))
+    })
+}
+
+// Returns the sum of the middle pages of the valid sequences and the index of the invalid ones
+fn process_sequences(sequences: &[Vec<i32>], rules: &Rules) -> (i32, Vec<usize>) {
+    let (valid, invalid): (Vec<usize>, Vec<usize>) =
+        (0..sequences.len()).partition(|&i| is_valid_sequence(&sequences[i], rules));
+    let total = valid
+        .iter()
+        .map(|&i| sequences[i][sequences[i].len() / 2])
+        .sum();
+    (total, invalid)
+}
+
+// The rules restricted to the pages of the sequence must form a DAG, its topological order is the fixed sequence
+fn sort_sequence(sequence: &[i32], rules: &Rules) -> Result<Vec<i32>> {
+    rules
+        .induced_subgraph(sequence)
+        .topological_sort()
+        .map_err(|cycle| {
+            Error::NoSolution(format!(
+                "the rules for sequence {:?} contain a cycle: {}",
+                sequence, cycle
+            ))
         })
-        .collect::<Result<Vec<i32>>>()
 }
 
-// Suppose x = before and y = after
-// For a given x we store all the y's that HAVE to come AFTER x
-fn add_rule(rules: &mut HashMap<i32, HashSet<i32>>, before: i32, after: i32) {
-    if !rules.contains_key(&before) {
-        rules.insert(before, HashSet::new());
+// part 2
+fn re_compute_bad_sequences(
+    sequences: &[Vec<i32>],
+    bad_sequences: &[usize],
+    rules: &Rules,
+) -> Result<i32> {
+    let mut total = 0;
+    for &sequence in bad_sequences {
+        let sorted = sort_sequence(&sequences[sequence], rules)?;
+        total += sorted[sorted.len() / 2];
     }
-    rules.get_mut(&before).unwrap().insert(after);
+    Ok(total)
 }
 
-fn read_input(file_path: &PathBuf) -> Result<(HashMap<i32, HashSet<i32>>, Vec<Vec<i32>>)> {
-    let input = fs::read_to_string(file_path).context("Failed to read input file")?;
-    let mut sequences: Vec<Vec<i32>> = Vec::new();
-    let mut rules: HashMap<i32, HashSet<i32>> = HashMap::new();
-
-    for line in input.lines() {
-        if line.contains("|") {
-            let parts: Vec<&str> = line.split("|").collect();
-            if parts.len() == 2 {
-                let values = convert_str_to_i32(parts)?;
-                add_rule(&mut rules, values[0], values[1]);
-            }
-        } else if line.contains(",") {
-            let parts: Vec<&str> = line.split(",").collect();
-            sequences.push(convert_str_to_i32(parts)?);
+const GENERATED_UPDATES: usize = 20;
+
+// Updates of `size` pages (odd so they have a middle page) taken from `size + 10` pages with a rule
+// between every pair of them, so the number of rules grows quadratically. Half of the updates are
+// in order, the others are shuffled
+fn generate(size: usize, rng: &mut Rng) -> String {
+    let size = size | 1;
+    let mut order: Vec<usize> = (10..size + 20).collect();
+    rng.shuffle(&mut order);
+    let mut input = String::new();
+    for (i, before) in order.iter().enumerate() {
+        for after in order[i + 1..].iter() {
+            input.push_str(&format!("{}|{}\n", before, after));
         }
     }
-    Ok((rules, sequences))
-}
-
-fn process_sequences(
-    sequences: Vec<Vec<i32>>,
-    rules: &HashMap<i32, HashSet<i32>>,
-) -> (i32, Vec<Vec<i32>>) {
-    let mut total = 0;
-    let mut bad_sequences: Vec<Vec<i32>> = Vec::new();
-
-    for sequence in sequences {
-        let mut mid_number = sequence[sequence.len() / 2];
-        // check that the number N = sequence[i] is in a correct location
-        // i.e in the `rules` the numbers N is supposed to be ahead of (rules[N]) are not already present
-        // at indices [0, i - 1]
-        for (i, item) in sequence.iter().enumerate() {
-            if i == 0 {
-                continue;
-            }
-            let values_supposed_to_come_after = match rules.get(item) {
-                Some(values) => values,
-                None => {
-                    continue;
-                }
-            };
-            let intersection = sequence[0..i]
-                .iter()
-                .filter(|&&x| values_supposed_to_come_after.contains(&x))
-                .collect::<Vec<&i32>>();
-
-            if intersection.len() > 0 {
-                bad_sequences.push(sequence);
-                mid_number = 0;
-                break;
-            }
+    input.push('\n');
+    for update in 0..GENERATED_UPDATES {
+        let mut ranks: Vec<usize> = (0..order.len()).collect();
+        rng.shuffle(&mut ranks);
+        ranks.truncate(size);
+        if update % 2 == 0 {
+            ranks.sort_unstable();
         }
-        total += mid_number;
+        let pages: Vec<String> = ranks.iter().map(|&rank| order[rank].to_string()).collect();
+        input.push_str(&pages.join(","));
+        input.push('\n');
     }
-    (total, bad_sequences)
+    input
 }
 
-fn safe_contains(map: Option<&HashSet<i32>>, value: &i32) -> bool {
-    match map {
-        Some(m) => m.contains(value),
-        None => false,
+pub const GENERATOR: Generator = Generator {
+    generate,
+    size_unit: "pages per update",
+    min_size: 8,
+    growth: 2.0,
+};
+
+struct Day;
+
+impl Solution for Day {
+    type Input = (Rules, Vec<Vec<i32>>);
+    // Index of the sequences breaking the rules
+    type Artifacts = Vec<usize>;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    const PART_1: &'static str = "The sum of valid sequences";
+    const PART_2: &'static str = "The sum of re-sorted invalid sequences";
+
+    fn parse(file_path: &Path) -> Result<Self::Input> {
+        cache::parse_cached(file_path, "day_5", include_str!("mod.rs"), read_input)
     }
-}
 
-// This is O(n) is unclear here, could be O(n^infinity) but I have to go eat dinner ...
-fn sort_sequence(mut sequence: Vec<i32>, rules: &HashMap<i32, HashSet<i32>>) -> Vec<i32> {
-    let sort_fn = |a: &i32, b: &i32| {
-        match (
-            safe_contains(rules.get(a), b),
-            safe_contains(rules.get(b), a),
-        ) {
-            (true, true) => {
-                // Consider raising an error
-                println!("Rule violation for {} and {}", a, b);
-                Ordering::Equal
-            }
-            (true, false) => Ordering::Less,
-            (false, true) => Ordering::Greater,
-            (false, false) => Ordering::Equal,
-        }
-    };
-    // maybe  clone?
-    sequence.sort_by(sort_fn);
-    sequence
-}
+    fn part_1((rules, sequences): &Self::Input) -> Result<(i32, Vec<usize>)> {
+        Ok(process_sequences(sequences, rules))
+    }
 
-// part 2
-pub fn re_compute_bad_sequences(
-    bad_sequences: Vec<Vec<i32>>,
-    rules: &HashMap<i32, HashSet<i32>>,
-) -> i32 {
-    let mut total = 0;
-    for mut sequence in bad_sequences {
-        sequence = sort_sequence(sequence, rules);
-        total += sequence[sequence.len() / 2];
+    fn part_2((rules, sequences): &Self::Input, bad_sequences: Vec<usize>) -> Result<i32> {
+        re_compute_bad_sequences(sequences, &bad_sequences, rules)
     }
-    total
 }
 
-pub fn solve(file_path: &PathBuf) -> Result<()> {
-    let (rules, sequences) = read_input(file_path)?;
-    let (sum_valid_sequences, bad_sequences) = process_sequences(sequences, &rules);
-    println!("The sum of valid sequences is: {}", sum_valid_sequences);
-    let sum_invalid_sequences = re_compute_bad_sequences(bad_sequences, &rules);
-    println!(
-        "The sum of re-sorted invalid sequences is: {}",
-        sum_invalid_sequences
-    );
-    Ok(())
+pub fn solve(file_path: &Path) -> Result<Report> {
+    solution::run::<Day>(file_path)
 }
@@ -1,208 +1,114 @@
-use anyhow::{anyhow, Context, Result};
-use std::collections::HashSet;
-use std::fs;
-use std::hash::Hash;
-use std::path::PathBuf;
-
-#[derive(Clone, Hash, Eq)]
-struct Increment {
-    row_incr: i32,
-    col_incr: i32,
-}
-
-impl PartialEq for Increment {
-    fn eq(&self, other: &Self) -> bool {
-        self.row_incr == other.row_incr && self.col_incr == other.col_incr
-    }
-}
-impl Increment {
-    fn new(row_incr: i32, col_incr: i32) -> Self {
-        Self { row_incr, col_incr }
-    }
-
-    fn increment(&self, row: i32, column: i32) -> (i32, i32) {
-        (row + self.row_incr, column + self.col_incr)
-    }
-
-    fn unsafe_increment(&self, row: usize, column: usize) -> (usize, usize) {
-        (
-            (row as i32 + self.row_incr) as usize,
-            (column as i32 + self.col_incr) as usize,
-        )
-    }
-
-    fn from_char(ch: char) -> Option<Self> {
-        match ch {
-            '^' => Some(Increment::up()),
-            'v' => Some(Increment::down()),
-            '>' => Some(Increment::right()),
-            '<' => Some(Increment::left()),
-            _ => None,
-        }
-    }
-
-    fn up() -> Self {
-        Increment::new(-1, 0)
-    }
-
-    fn right() -> Self {
-        Increment::new(0, 1)
-    }
-
-    fn down() -> Self {
-        Increment::new(1, 0)
-    }
-
-    fn left() -> Self {
-        Increment::new(0, -1)
-    }
-
-    fn next(&self) -> Increment {
-        if *self == Increment::up() {
-            return Increment::right();
-        } else if *self == Increment::right() {
-            return Increment::down();
-        } else if *self == Increment::down() {
-            return Increment::left();
-        } else if *self == Increment::left() {
-            return Increment::up();
-        }
-        println!(
-            "unexpected increment values {}, {}",
-            self.row_incr, self.col_incr
-        );
-        Increment::up()
-    }
-}
+use crate::bitgrid::BitGrid;
+use crate::cache::{self, Cacheable};
+use crate::cycle;
+use crate::error::{Error, Result};
+use crate::generate::{Generator, Rng};
+use crate::grid::Grid;
+use crate::parse::Source;
+use crate::point::{Direction, Point};
+use crate::render::{Canvas, Color, Style};
+use crate::report::Report;
+use crate::simulation::Simulation;
+use crate::solution::{self, Solution};
+use std::path::Path;
 
 #[derive(Clone)]
 struct GuardMap {
-    obstacles: HashSet<(usize, usize)>,
-    max_rows: Option<usize>,
-    max_cols: Option<usize>,
-    initial_position: Option<(usize, usize)>,
-    initial_direction: Option<Increment>,
+    obstacles: BitGrid,
+    initial_position: Point,
+    initial_direction: Direction,
 }
 
-impl GuardMap {
-    fn new() -> Self {
-        GuardMap {
-            obstacles: HashSet::new(),
-            max_rows: None,
-            max_cols: None,
-            initial_position: None,
-            initial_direction: None,
-        }
-    }
-    fn is_set(&self) -> bool {
-        self.max_rows.is_some()
-            && self.max_cols.is_some()
-            && self.initial_direction.is_some()
-            && self.initial_direction.is_some()
-    }
-
-    fn is_within_map(&self, row: i32, column: i32) -> bool {
-        row <= self.max_rows.unwrap_or(0) as i32
-            && column <= self.max_cols.unwrap_or(0) as i32
-            && 0 <= row
-            && 0 <= column
+impl Cacheable for GuardMap {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.obstacles.write(out);
+        self.initial_position.write(out);
+        self.initial_direction.write(out);
     }
 
-    fn is_obstacle(&self, row: usize, column: usize) -> bool {
-        self.obstacles.contains(&(row, column))
+    fn read(input: &mut &[u8]) -> Option<Self> {
+        Some(GuardMap {
+            obstacles: BitGrid::read(input)?,
+            initial_position: Point::read(input)?,
+            initial_direction: Direction::read(input)?,
+        })
     }
 }
 
-fn read_input(file_path: &PathBuf) -> Result<GuardMap> {
-    let input = fs::read_to_string(file_path).context("Failed to read input file")?;
-    let mut guard_map = GuardMap::new();
-    let mut last_row = 0;
-    for (row, line) in input.lines().enumerate() {
-        if line.len() == 0 {
-            continue;
-        }
-        for (column, char) in line.chars().enumerate() {
-            if char == '#' {
-                guard_map.obstacles.insert((row, column));
-            }
-            if guard_map.initial_position.is_none() {
-                match Increment::from_char(char) {
-                    Some(increment) => {
-                        guard_map.initial_direction = Some(increment);
-                        guard_map.initial_position = Some((row, column));
-                    }
-                    None => {}
-                }
-            }
-        }
-        if row == 0 {
-            guard_map.max_cols = Some(line.chars().count());
-        }
-        last_row = row;
-    }
-    guard_map.max_rows = Some(last_row);
-    if guard_map.initial_position.is_none() {
-        return Err(anyhow!("did not find initial position of the guard"));
+fn read_input(file_path: &Path) -> Result<GuardMap> {
+    let map = Source::read(file_path)?.char_grid()?;
+    let (initial_position, initial_direction) = map
+        .iter()
+        .find_map(|(position, &char)| {
+            Direction::from_arrow(char).map(|direction| (Point::from_position(position), direction))
+        })
+        .ok_or_else(|| {
+            Error::InvalidInput("did not find initial position of the guard".to_string())
+        })?;
+    let mut obstacles = BitGrid::new(map.rows(), map.columns());
+    for position in map.find_all(|&char| char == '#') {
+        obstacles.insert(Point::from_position(position));
     }
-    Ok(guard_map)
+    Ok(GuardMap {
+        obstacles,
+        initial_position,
+        initial_direction,
+    })
 }
 
 // returns (whether next location is an obstacle, whether next location is outside the map)
 fn checknext_point(
-    current_point: (usize, usize),
-    current_direction: &Increment,
+    current_point: Point,
+    current_direction: Direction,
     guard_map: &GuardMap,
 ) -> (bool, bool) {
-    let next_point = current_direction.increment(current_point.0 as i32, current_point.1 as i32);
-    if !guard_map.is_within_map(next_point.0, next_point.1) {
+    let next_point = current_point + current_direction;
+    if !guard_map.obstacles.in_bounds(next_point) {
         return (false, true);
     }
-    let (next_row, next_col) = (next_point.0 as usize, next_point.1 as usize);
-    if guard_map.is_obstacle(next_row, next_col) {
-        return (true, false);
-    }
-    (false, false)
+    (guard_map.obstacles.contains(next_point), false)
 }
 
+// returns false when the guard was already there facing the same direction, i.e. it is stuck in an infinite loop
 fn mark_location_as_visited(
-    locations: &mut HashSet<(usize, usize, Increment)>,
-    new_location: (usize, usize),
-    direction: Increment,
-) -> Result<()> {
-    if locations.contains(&(new_location.0, new_location.1, direction.clone())) {
-        return Err(anyhow!(
-            "Stuck in an infinite loop at {}, {}",
-            new_location.0,
-            new_location.1
-        ));
-    }
-    locations.insert((new_location.0, new_location.1, direction));
-    Ok(())
+    locations: &mut GuardPath,
+    new_location: Point,
+    direction: Direction,
+) -> bool {
+    locations.insert_directed(new_location, direction)
 }
 
+// locations visited along with the direction the guard was facing
+type GuardPath = BitGrid;
+
 // returns the guard path and whether the path is an infinite loop
-fn run_guard_path(guard_map: &GuardMap) -> Result<(HashSet<(usize, usize, Increment)>, bool)> {
+fn run_guard_path(guard_map: &GuardMap) -> Result<(GuardPath, bool)> {
+    walk_guard_path(guard_map, |_, _| {})
+}
+
+// `run_guard_path` calling `on_step` with every location in the order the guard walks them
+fn walk_guard_path<F: FnMut(Point, Direction)>(
+    guard_map: &GuardMap,
+    mut on_step: F,
+) -> Result<(GuardPath, bool)> {
     // locations visited and the guard direction while visiting it to detect infinite loops
-    let mut locations: HashSet<(usize, usize, Increment)> = HashSet::new();
-    if !guard_map.is_set() {
-        return Err(anyhow!("Map is not properly setup"));
-    }
-    let mut current_point = guard_map.initial_position.unwrap().clone();
-    let mut current_direction = guard_map.initial_direction.as_ref().unwrap().clone();
-    let mut infinite_loop_err =
-        mark_location_as_visited(&mut locations, current_point, current_direction.clone());
+    let mut locations: GuardPath =
+        BitGrid::with_directions(guard_map.obstacles.rows(), guard_map.obstacles.columns());
+    let mut current_point = guard_map.initial_position;
+    let mut current_direction = guard_map.initial_direction;
+    on_step(current_point, current_direction);
+    mark_location_as_visited(&mut locations, current_point, current_direction);
 
     loop {
         let (is_obstacle, is_outside_map) =
-            checknext_point(current_point, &current_direction, &guard_map);
+            checknext_point(current_point, current_direction, guard_map);
         if is_outside_map {
             return Ok((locations, false));
         }
         if !is_obstacle {
-            current_point = current_direction.unsafe_increment(current_point.0, current_point.1);
-            infinite_loop_err =
-                mark_location_as_visited(&mut locations, current_point, current_direction.clone());
-            if infinite_loop_err.is_err() {
+            current_point += current_direction;
+            on_step(current_point, current_direction);
+            if !mark_location_as_visited(&mut locations, current_point, current_direction) {
                 return Ok((locations, true));
             }
             continue;
@@ -210,21 +116,16 @@ fn run_guard_path(guard_map: &GuardMap) -> Result<(HashSet<(usize, usize, Increm
 
         let mut found = false;
         for _ in 0..2 {
-            current_direction = current_direction.next();
+            current_direction = current_direction.turn_right();
             let (is_obstacle, is_outside_map) =
-                checknext_point(current_point, &current_direction, &guard_map);
+                checknext_point(current_point, current_direction, guard_map);
             if is_outside_map {
                 return Ok((locations, false));
             }
             if !is_obstacle {
-                current_point =
-                    current_direction.unsafe_increment(current_point.0, current_point.1);
-                infinite_loop_err = mark_location_as_visited(
-                    &mut locations,
-                    current_point,
-                    current_direction.clone(),
-                );
-                if infinite_loop_err.is_err() {
+                current_point += current_direction;
+                on_step(current_point, current_direction);
+                if !mark_location_as_visited(&mut locations, current_point, current_direction) {
                     return Ok((locations, true));
                 }
                 found = true;
@@ -232,74 +133,230 @@ fn run_guard_path(guard_map: &GuardMap) -> Result<(HashSet<(usize, usize, Increm
             }
         }
         if !found {
-            return Err(anyhow!(
+            return Err(Error::InvalidInput(format!(
                 "The guard is unexpectadly stuck on the map at ({}, {}), this should not happen",
-                current_point.0,
-                current_point.1
-            ));
+                current_point.row, current_point.column
+            )));
         }
     }
 }
 
-fn check_is_infinite_loop(mut guard_map: GuardMap, extra_obstacle: (usize, usize)) -> Result<bool> {
-    guard_map.obstacles.insert(extra_obstacle);
-    let guard_path = run_guard_path(&guard_map)?;
-    return Ok(guard_path.1);
+type GuardState = (Point, Direction);
+
+// One move of the guard: turn right in front of an obstacle, otherwise step forward.
+// `None` once the guard leaves the map
+fn next_guard_state(guard_map: &GuardMap, (point, direction): GuardState) -> Option<GuardState> {
+    let next_point = point + direction;
+    if !guard_map.obstacles.in_bounds(next_point) {
+        return None;
+    }
+    match guard_map.obstacles.contains(next_point) {
+        true => Some((point, direction.turn_right())),
+        false => Some((next_point, direction)),
+    }
+}
+
+// The extra obstacle is placed on the map for the check and removed afterwards
+fn check_is_infinite_loop(guard_map: &mut GuardMap, extra_obstacle: Point) -> Result<bool> {
+    if !guard_map.obstacles.in_bounds(extra_obstacle) {
+        return Err(Error::Internal(
+            "extra obstacle is outside of the map".to_string(),
+        ));
+    }
+    let added = guard_map.obstacles.insert(extra_obstacle);
+    let start = (guard_map.initial_position, guard_map.initial_direction);
+    let is_loop = cycle::has_cycle(start, |&state| next_guard_state(guard_map, state));
+    if added {
+        guard_map.obstacles.remove(extra_obstacle);
+    }
+    Ok(is_loop)
 }
 
 // Definitively not the most perfmant solution, this is brute force
 // we test every unique location, the guard would visit and add an obstacle there
 // before re-running the path with the extra obstacle and check if we are stuck in an infinite loop
-fn find_infinite_loop_locations(
+fn find_infinite_loop_obstacles(
     guard_map: &GuardMap,
-    guard_path: &mut HashSet<(usize, usize)>,
-) -> Result<usize> {
-    let mut total = 0;
-    guard_path.remove(&(
-        guard_map.initial_position.unwrap().0,
-        guard_map.initial_position.unwrap().1,
-    ));
-    for location in guard_path.iter() {
-        if check_is_infinite_loop(guard_map.clone(), *location)? {
-            total += 1
+    guard_path: &mut BitGrid,
+) -> Result<Vec<Point>> {
+    let mut obstacles = Vec::new();
+    guard_path.remove(guard_map.initial_position);
+    // a single copy of the map shared by every check
+    let mut guard_map = guard_map.clone();
+    for location in guard_path.cells() {
+        if check_is_infinite_loop(&mut guard_map, location)? {
+            obstacles.push(location);
         }
     }
-    Ok(total)
+    Ok(obstacles)
+}
+
+fn find_infinite_loop_locations(guard_map: &GuardMap, guard_path: &mut BitGrid) -> Result<usize> {
+    Ok(find_infinite_loop_obstacles(guard_map, guard_path)?.len())
+}
+
+fn extract_unique_locations(guard_path: GuardPath) -> BitGrid {
+    guard_path.cell_set()
 }
 
-fn extract_unique_locations(
-    guard_path: HashSet<(usize, usize, Increment)>,
-) -> HashSet<(usize, usize)> {
-    let mut unique_locations: HashSet<(usize, usize)> = HashSet::new();
-    for entry in guard_path {
-        unique_locations.insert((entry.0, entry.1));
+// Obstacles drawn as `#` on an empty map
+fn obstacle_map(guard_map: &GuardMap) -> Grid<char> {
+    let obstacles = &guard_map.obstacles;
+    let mut map = Grid::new(obstacles.rows(), obstacles.columns(), '.');
+    for point in obstacles.cells() {
+        *map.get_point_mut(point).unwrap() = '#';
     }
-    let mut check: HashSet<(usize, usize)> = HashSet::new();
-    for l in unique_locations.iter() {
-        if check.contains(&l) {
-            println!("dupe found ({}, {})", l.0, l.1);
-            continue;
+    map
+}
+
+// Guard path drawn with the direction it was walked in, `+` where it crosses itself
+pub fn render(file_path: &Path) -> Result<Canvas> {
+    let guard_map = read_input(file_path)?;
+    let (guard_path, _) = run_guard_path(&guard_map)?;
+    let mut canvas = Canvas::new(obstacle_map(&guard_map));
+    let path = canvas.add_layer("guard path", Style::fg(Color::Cyan));
+    for point in guard_path.cells() {
+        let mut directions = Direction::ORTHOGONAL
+            .into_iter()
+            .filter(|&direction| guard_path.contains_directed(point, direction));
+        let glyph = match (directions.next(), directions.next()) {
+            (Some(direction), None) => direction.arrow(),
+            _ => '+',
+        };
+        path.mark_with(point, glyph);
+    }
+    canvas
+        .add_layer("guard start", Style::fg(Color::Yellow).bold())
+        .mark_with(
+            guard_map.initial_position,
+            guard_map.initial_direction.arrow(),
+        );
+    let mut unique_locations = extract_unique_locations(guard_path);
+    let loop_obstacles = find_infinite_loop_obstacles(&guard_map, &mut unique_locations)?;
+    let layer = canvas.add_layer("loop obstacles", Style::fg(Color::Red).bold());
+    for obstacle in loop_obstacles {
+        layer.mark_with(obstacle, 'O');
+    }
+    Ok(canvas)
+}
+
+// Replays the guard walk one move at a time
+struct GuardSimulation {
+    // Obstacles and the path walked so far
+    canvas: Canvas,
+    steps: Vec<(Point, Direction)>,
+    current: usize,
+}
+
+impl Simulation for GuardSimulation {
+    fn step(&mut self) -> bool {
+        if self.current + 1 >= self.steps.len() {
+            return false;
         }
-        check.insert(*l);
+        let (point, direction) = self.steps[self.current];
+        let layer = self.canvas.layer_mut("guard path").unwrap();
+        // crossings keep the `+` once drawn
+        let glyph = match layer.weight(point) {
+            0 => direction.arrow(),
+            _ => '+',
+        };
+        layer.mark_with(point, glyph);
+        self.current += 1;
+        true
+    }
+
+    fn frame(&self) -> Canvas {
+        let mut canvas = self.canvas.clone();
+        let (point, direction) = self.steps[self.current];
+        canvas
+            .add_layer("guard", Style::fg(Color::Yellow).bold())
+            .mark_with(point, direction.arrow());
+        canvas
+    }
+
+    fn status(&self) -> String {
+        let (point, direction) = self.steps[self.current];
+        format!(
+            "step {}/{}, guard at ({}, {}) facing {}",
+            self.current + 1,
+            self.steps.len(),
+            point.row,
+            point.column,
+            direction.arrow()
+        )
     }
-    unique_locations
 }
 
-pub fn solve(file_path: &PathBuf) -> Result<()> {
+pub fn simulate(file_path: &Path) -> Result<Box<dyn Simulation>> {
     let guard_map = read_input(file_path)?;
-    let guard_path = run_guard_path(&guard_map)?;
-    let mut guard_path_unique_locations = extract_unique_locations(guard_path.0);
-    println!(
-        "the guard visits {} locations",
-        guard_path_unique_locations.len()
-    );
-
-    let number_of_potential_infinite_loop =
-        find_infinite_loop_locations(&guard_map, &mut guard_path_unique_locations)?;
-    println!(
-        "the number of potential infinite loop is {}",
-        number_of_potential_infinite_loop
-    );
-
-    Ok(())
+    let mut steps = Vec::new();
+    walk_guard_path(&guard_map, |point, direction| {
+        steps.push((point, direction))
+    })?;
+    let mut canvas = Canvas::new(obstacle_map(&guard_map));
+    canvas.add_layer("guard path", Style::fg(Color::Cyan));
+    Ok(Box::new(GuardSimulation {
+        canvas,
+        steps,
+        current: 0,
+    }))
+}
+
+// Square grid with 5% of obstacles like the puzzle input, the guard starts in the middle facing up
+fn generate(size: usize, rng: &mut Rng) -> String {
+    let mut input = String::new();
+    for row in 0..size {
+        for column in 0..size {
+            input.push(
+                match (row == size / 2 && column == size / 2, rng.chance(0.05)) {
+                    (true, _) => '^',
+                    (false, true) => '#',
+                    (false, false) => '.',
+                },
+            );
+        }
+        input.push('\n');
+    }
+    input
+}
+
+pub const GENERATOR: Generator = Generator {
+    generate,
+    size_unit: "grid side",
+    min_size: 16,
+    growth: 2.0,
+};
+
+struct Day;
+
+impl Solution for Day {
+    type Input = GuardMap;
+    // Locations visited by the guard, the candidates for an extra obstacle
+    type Artifacts = BitGrid;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    const PART_1: &'static str = "the number of locations visited by the guard";
+    const PART_2: &'static str = "the number of potential infinite loop";
+
+    fn parse(file_path: &Path) -> Result<Self::Input> {
+        cache::parse_cached(file_path, "day_6", include_str!("mod.rs"), read_input)
+    }
+
+    fn part_1(guard_map: &Self::Input) -> Result<(usize, BitGrid)> {
+        let guard_path = run_guard_path(guard_map)?;
+        let guard_path_unique_locations = extract_unique_locations(guard_path.0);
+        Ok((
+            guard_path_unique_locations.count_cells(),
+            guard_path_unique_locations,
+        ))
+    }
+
+    fn part_2(guard_map: &Self::Input, mut guard_path_unique_locations: BitGrid) -> Result<usize> {
+        find_infinite_loop_locations(guard_map, &mut guard_path_unique_locations)
+    }
+}
+
+pub fn solve(file_path: &Path) -> Result<Report> {
+    solution::run::<Day>(file_path)
 }
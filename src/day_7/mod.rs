@@ -1,6 +1,12 @@
-use anyhow::{Context, Result};
-use std::fs;
-use std::path::PathBuf;
+use crate::cache::{self, Cacheable};
+use crate::error::Result;
+use crate::generate::{Generator, Rng};
+use crate::input::LineReader;
+use crate::math;
+use crate::report::Report;
+use crate::search::search;
+use crate::solution::{self, Solution};
+use std::path::Path;
 
 #[derive(Debug, Clone, Copy)]
 enum Operation {
@@ -10,112 +16,156 @@ enum Operation {
 }
 
 impl Operation {
-    fn compute(&self, a: i64, b: i64) -> i64 {
+    // `None` on overflow
+    fn compute(&self, a: i64, b: i64) -> Option<i64> {
         match self {
-            Self::Add => a + b,
-            Self::Multiply => a * b,
-            // We are doing an unwrap here because we control a and b
-            Self::Concat => (a.to_string() + b.to_string().as_str())
-                .parse::<i64>()
-                .unwrap(),
+            Self::Add => a.checked_add(b),
+            Self::Multiply => a.checked_mul(b),
+            Self::Concat => math::checked_concat(a, b),
         }
     }
 }
 
-fn all_combinations(values: &[Operation], n: usize) -> Vec<Vec<Operation>> {
-    if n == 0 {
-        return vec![vec![]];
-    }
-
-    let mut result = Vec::new();
-    // First, recursively get all combinations of length (n - 1)
-    let shorter = all_combinations(values, n - 1);
-
-    // For each combination of length (n - 1), append each possible value to create length-n combos
-    for combo in shorter {
-        for &v in values {
-            let mut new_combo = combo.clone();
-            new_combo.push(v);
-            result.push(new_combo);
-        }
-    }
-    result
-}
-
 #[derive(Debug)]
 struct Equation {
     output: i64,
     inputs: Vec<i64>,
 }
 
+impl Cacheable for Equation {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.output.write(out);
+        self.inputs.write(out);
+    }
+
+    fn read(input: &mut &[u8]) -> Option<Self> {
+        Some(Equation {
+            output: i64::read(input)?,
+            inputs: Vec::read(input)?,
+        })
+    }
+}
+
 impl Equation {
     fn compute_valid_solution(&self, all_ops: &[Operation]) -> i64 {
-        for ops in all_combinations(all_ops, self.inputs.len() - 1) {
-            if self.is_valid_solution(&ops) {
-                return self.output;
-            }
+        // with positive inputs no operation makes the result smaller,
+        // so operators are only tried while the running value does not exceed the output,
+        // values overflowing can never reach it either
+        let can_prune = self.inputs.iter().all(|&input| input > 0);
+        let ops = search(
+            all_ops,
+            self.inputs.len() - 1,
+            self.inputs[0],
+            |&acc, op, depth| {
+                let value = op.compute(acc, self.inputs[depth + 1])?;
+                (!can_prune || value <= self.output).then_some(value)
+            },
+            |&acc| acc == self.output,
+        );
+        match ops {
+            Some(_) => self.output,
+            None => 0,
         }
-        0
     }
+}
 
-    fn is_valid_solution(&self, ops: &[Operation]) -> bool {
-        if ops.len() != self.inputs.len() - 1 {
-            return false;
+fn read_input(file_path: &Path) -> Result<Vec<Equation>> {
+    let mut reader = LineReader::open(file_path)?;
+    let mut equations = Vec::new();
+    while let Some(line) = reader.next_content_line()? {
+        let (output, inputs) = line.key_values(':')?;
+        if inputs.is_empty() {
+            return Err(line.error("equation has no inputs".to_string()).into());
         }
+        equations.push(Equation { output, inputs });
+    }
+    Ok(equations)
+}
 
-        self.inputs
-            .iter()
-            .skip(1)
-            .zip(ops)
-            .fold(self.inputs[0], |acc, (&val, &op)| op.compute(acc, val))
-            == self.output
+// Returns the sum of the outputs of the equations that can be solved and the index of the others
+fn sum_valid_solutions(
+    equations: &[Equation],
+    candidates: &[usize],
+    all_ops: &[Operation],
+) -> (i64, Vec<usize>) {
+    let mut total = 0;
+    let mut unsolved = Vec::new();
+    for &i in candidates {
+        match equations[i].compute_valid_solution(all_ops) {
+            0 => unsolved.push(i),
+            value => total += value,
+        }
     }
+    (total, unsolved)
 }
 
-fn read_input(file_path: &PathBuf) -> Result<Vec<Equation>> {
-    let input = fs::read_to_string(file_path)?;
+const GENERATED_EQUATIONS: usize = 40;
+// Keeps the sum of the test values within an i64
+const MAX_GENERATED_VALUE: i64 = 1_000_000_000_000_000;
+
+// Equations of `size` operands, half of them reach their test value with random operators and the
+// others miss it by one, which usually forces a full search
+fn generate(size: usize, rng: &mut Rng) -> String {
+    let ops = [Operation::Add, Operation::Multiply, Operation::Concat];
+    let mut input = String::new();
+    for equation in 0..GENERATED_EQUATIONS {
+        let inputs: Vec<i64> = (0..size).map(|_| rng.range(1, 9)).collect();
+        let output = inputs[1..].iter().fold(inputs[0], |acc, &value| {
+            rng.pick(&ops)
+                .compute(acc, value)
+                .filter(|&result| result <= MAX_GENERATED_VALUE)
+                .unwrap_or(acc + value)
+        }) + (equation % 2) as i64;
+        let inputs: Vec<String> = inputs.iter().map(|value| value.to_string()).collect();
+        input.push_str(&format!("{}: {}\n", output, inputs.join(" ")));
+    }
     input
-        .lines()
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            let (output, inputs) = line
-                .split_once(':')
-                .context(format!("Invalid line format, missing ':', `{}`", line))?;
-            Ok(Equation {
-                output: output
-                    .trim()
-                    .parse()
-                    .context(format!("Failed to parse output for line: `{}`", line))?,
-                inputs: inputs
-                    .trim_start()
-                    .split(' ')
-                    .map(|n| n.trim().parse())
-                    .collect::<std::result::Result<_, _>>()
-                    .context(format!("Failed to parse inputs: `{}`", inputs))?,
-            })
-        })
-        .collect()
 }
 
-pub fn solve(file_path: &PathBuf) -> Result<()> {
-    let mut total = 0;
-    let equations = read_input(file_path)?;
-    // part 1:
-    for eq in equations.iter() {
-        total += eq.compute_valid_solution(&[Operation::Add, Operation::Multiply]);
+pub const GENERATOR: Generator = Generator {
+    generate,
+    size_unit: "operands per equation",
+    min_size: 4,
+    growth: 1.3,
+};
+
+struct Day;
+
+impl Solution for Day {
+    type Input = Vec<Equation>;
+    // Total of part 1 and the index of the equations it could not solve
+    type Artifacts = (i64, Vec<usize>);
+    type Part1 = i64;
+    type Part2 = i64;
+
+    const PART_1: &'static str = "the total value";
+    const PART_2: &'static str = "the total value when including concat operator";
+
+    fn parse(file_path: &Path) -> Result<Self::Input> {
+        cache::parse_cached(file_path, "day_7", include_str!("mod.rs"), read_input)
     }
-    println!("the total value is {}", total);
-    let mut total = 0;
 
-    // part 2:
-    for eq in equations.iter() {
-        total +=
-            eq.compute_valid_solution(&[Operation::Add, Operation::Multiply, Operation::Concat]);
+    fn part_1(equations: &Self::Input) -> Result<(i64, (i64, Vec<usize>))> {
+        let all_equations: Vec<usize> = (0..equations.len()).collect();
+        let (total, unsolved) = sum_valid_solutions(
+            equations,
+            &all_equations,
+            &[Operation::Add, Operation::Multiply],
+        );
+        Ok((total, (total, unsolved)))
     }
-    println!(
-        "the total value when including concat operator is {}",
-        total
-    );
 
-    Ok(())
+    // equations solved without concatenation are still solved with it, only the others are searched again
+    fn part_2(equations: &Self::Input, (total, unsolved): (i64, Vec<usize>)) -> Result<i64> {
+        let (concat_total, _) = sum_valid_solutions(
+            equations,
+            &unsolved,
+            &[Operation::Add, Operation::Multiply, Operation::Concat],
+        );
+        Ok(total + concat_total)
+    }
+}
+
+pub fn solve(file_path: &Path) -> Result<Report> {
+    solution::run::<Day>(file_path)
 }
@@ -1,96 +1,84 @@
-use anyhow::{anyhow, Result};
+use crate::bitgrid::BitGrid;
+use crate::error::{Error, Result};
+use crate::generate::{Generator, Rng};
+use crate::grid::Grid;
+use crate::math;
+use crate::parse::Source;
+use crate::point::Point;
+use crate::render::{Canvas, Color, Style};
+use crate::report::Report;
+use crate::solution::{self, Solution};
 use itertools::Itertools;
-use num::Integer;
 use std::collections::HashMap;
-use std::path::PathBuf;
-use std::{collections::HashSet, fs};
+use std::path::Path;
 
 struct Map {
-    max_row: i32,
-    max_column: i32,
-    nodes: HashMap<char, Vec<(i32, i32)>>,
+    grid: Grid<char>,
+    nodes: HashMap<char, Vec<Point>>,
 }
 
 impl Map {
-    fn is_within_map(&self, point: &(i32, i32)) -> bool {
-        0 <= point.0 && point.0 <= self.max_row && 0 <= point.1 && point.1 <= self.max_column
+    fn is_within_map(&self, point: &Point) -> bool {
+        self.grid.contains_point(*point)
     }
 }
 
-fn read_input(file_path: &PathBuf) -> Result<Map> {
-    let input = fs::read_to_string(file_path)?;
-    let mut nodes: HashMap<char, Vec<(i32, i32)>> = HashMap::new();
-    let mut max_row: i32 = 0;
-    let mut max_col: i32 = 0;
-    for (row, line) in input.lines().enumerate() {
-        if line.is_empty() {
+fn read_input(file_path: &Path) -> Result<Map> {
+    let grid = Source::read(file_path)?.char_grid()?;
+    if grid.is_empty() {
+        return Err(Error::InvalidInput("node map is empty".to_string()));
+    }
+    let mut nodes: HashMap<char, Vec<Point>> = HashMap::new();
+    for (position, &char) in grid.iter() {
+        if char == '.' {
             continue;
         }
-        for (col, char) in line.chars().enumerate() {
-            if char == '.' {
-                continue;
-            }
-            if !nodes.contains_key(&char) {
-                nodes.insert(char, Vec::new());
-            }
-            nodes.get_mut(&char).unwrap().push((row as i32, col as i32));
-        }
-        max_col = (line.len() - 1) as i32;
-        max_row = row as i32;
-    }
-    if max_col == 0 || max_row == 0 {
-        return Err(anyhow!("node map is empty"));
+        nodes
+            .entry(char)
+            .or_default()
+            .push(Point::from_position(position));
     }
-    Ok(Map {
-        max_row: max_row,
-        max_column: max_col,
-        nodes: nodes,
-    })
+    Ok(Map { grid, nodes })
 }
 
 // part 1 helper
-fn find_antinode_for_points_double_spaced(
-    point: &(i32, i32),
-    other: &(i32, i32),
-    map: &Map,
-) -> Vec<(i32, i32)> {
-    let diff = (point.0 - other.0, point.1 - other.1);
+fn find_antinode_for_points_double_spaced(point: &Point, other: &Point, map: &Map) -> Vec<Point> {
+    let diff = *point - *other;
     // Should not happen but just a safety
-    if diff.0 == 0 && diff.1 == 1 {
+    if diff == Point::new(0, 1) {
         return vec![];
     }
-    let nodes = vec![
-        (point.0 + diff.0, point.1 + diff.1),
-        (other.0 - diff.0, other.1 - diff.1),
-    ];
+    let nodes = [*point + diff, *other - diff];
     nodes
         .iter()
         .filter(|&p| map.is_within_map(p))
         .copied()
-        .collect::<Vec<(i32, i32)>>()
+        .collect::<Vec<Point>>()
 }
 
 // part 1 helper
-fn get_antinodes_double_spaced(map: &Map) -> usize {
-    let mut antinodes: HashSet<(i32, i32)> = HashSet::new();
+fn get_antinodes_double_spaced(map: &Map) -> BitGrid {
+    let mut antinodes = BitGrid::new(map.grid.rows(), map.grid.columns());
     for nodes in map.nodes.values() {
         nodes
             .iter()
             .combinations(2)
             .map(|pair| find_antinode_for_points_double_spaced(pair[0], pair[1], map))
             .for_each(|points| {
-                antinodes.extend(points);
+                for point in points {
+                    antinodes.insert(point);
+                }
             });
     }
-    antinodes.len()
+    antinodes
 }
 
 // part 2 helper
-fn find_points_in_diagonal(point: &(i32, i32), diff: &(i32, i32), map: &Map) -> Vec<(i32, i32)> {
-    let mut points: Vec<(i32, i32)> = vec![];
+fn find_points_in_diagonal(point: &Point, diff: &Point, map: &Map) -> Vec<Point> {
+    let mut points: Vec<Point> = vec![];
     let mut current_point = *point;
     loop {
-        let next_point = (current_point.0 + diff.0, current_point.1 + diff.1);
+        let next_point = current_point + *diff;
         if !map.is_within_map(&next_point) {
             break;
         }
@@ -100,50 +88,117 @@ fn find_points_in_diagonal(point: &(i32, i32), diff: &(i32, i32), map: &Map) ->
     points
 }
 
-fn make_vector_smaller(diff: (i32, i32)) -> (i32, i32) {
-    let gcd = diff.0.gcd(&diff.1);
-    (diff.0 / gcd, diff.1 / gcd)
+// Smallest vector with the same direction, the zero vector is returned as is
+fn make_vector_smaller(diff: Point) -> Point {
+    match math::gcd(diff.row, diff.column) {
+        0 => diff,
+        gcd => Point::new(diff.row / gcd, diff.column / gcd),
+    }
 }
 
 // part 2 helper
-fn get_antinodes_in_diagonal(point: &(i32, i32), other: &(i32, i32), map: &Map) -> Vec<(i32, i32)> {
-    let diff = make_vector_smaller((point.0 - other.0, point.1 - other.1));
+fn get_antinodes_in_diagonal(point: &Point, other: &Point, map: &Map) -> Vec<Point> {
+    let diff = make_vector_smaller(*point - *other);
     // Should not happen but just a safety
-    if diff.0 == 0 && diff.1 == 1 {
+    if diff == Point::new(0, 1) {
         return vec![];
     }
-    let mut points: Vec<(i32, i32)> = find_points_in_diagonal(point, &diff, map);
-    points.extend(find_points_in_diagonal(point, &(-diff.0, -diff.1), map));
+    let mut points: Vec<Point> = find_points_in_diagonal(point, &diff, map);
+    points.extend(find_points_in_diagonal(point, &-diff, map));
     // need to add the starting point if at least 1 other point was found
-    if points.len() > 0 {
+    if !points.is_empty() {
         points.push(*point);
     }
-    return points;
+    points
 }
 
 // part 2 helper
-fn get_antidoes_by_line(map: &Map) -> usize {
-    let mut antinodes: HashSet<(i32, i32)> = HashSet::new();
+fn get_antidoes_by_line(map: &Map) -> BitGrid {
+    let mut antinodes = BitGrid::new(map.grid.rows(), map.grid.columns());
     for nodes in map.nodes.values() {
         nodes
             .iter()
             .combinations(2)
             .map(|pair| get_antinodes_in_diagonal(pair[0], pair[1], map))
             .for_each(|points| {
-                antinodes.extend(points);
+                for point in points {
+                    antinodes.insert(point);
+                }
             });
     }
-    antinodes.len()
+    antinodes
 }
 
-pub fn solve(file_path: &PathBuf) -> Result<()> {
+pub fn render(file_path: &Path) -> Result<Canvas> {
     let map = read_input(file_path)?;
-    let antinodes_count_double_spaced = get_antinodes_double_spaced(&map);
-    println!(
-        "There are {} antinodes for double space rule",
-        antinodes_count_double_spaced
-    );
-    let antinodes_count_line = get_antidoes_by_line(&map);
-    println!("There are {} antinodes for line rule", antinodes_count_line);
-    Ok(())
+    let mut canvas = Canvas::new(map.grid.clone());
+    // antinodes shared by several pairs of antennas are marked several times, making a heatmap
+    let heatmap = canvas.add_layer("antinodes for line rule", Style::bg(Color::Blue));
+    for nodes in map.nodes.values() {
+        for pair in nodes.iter().combinations(2) {
+            heatmap.extend(get_antinodes_in_diagonal(pair[0], pair[1], &map));
+        }
+    }
+    canvas
+        .add_layer("antinodes for double space rule", Style::bg(Color::Red))
+        .extend(get_antinodes_double_spaced(&map).cells());
+    canvas
+        .add_layer("antennas", Style::fg(Color::Yellow).bold())
+        .extend(map.nodes.values().flatten().copied());
+    Ok(canvas)
+}
+
+const FREQUENCIES: &str = "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+// Square grid of side `size` with about `3 * size` antennas spread over 36 frequencies,
+// close to the density of the puzzle input
+fn generate(size: usize, rng: &mut Rng) -> String {
+    let frequencies: Vec<char> = FREQUENCIES.chars().take(36).collect();
+    let mut input = String::new();
+    for _ in 0..size {
+        input.extend((0..size).map(|_| {
+            if rng.chance(3.0 / size as f64) {
+                *rng.pick(&frequencies)
+            } else {
+                '.'
+            }
+        }));
+        input.push('\n');
+    }
+    input
+}
+
+pub const GENERATOR: Generator = Generator {
+    generate,
+    size_unit: "grid side",
+    min_size: 16,
+    growth: 2.0,
+};
+
+struct Day;
+
+impl Solution for Day {
+    type Input = Map;
+    type Artifacts = ();
+    type Part1 = usize;
+    type Part2 = usize;
+
+    const PART_1: &'static str = "antinodes for double space rule";
+    const PART_2: &'static str = "antinodes for line rule";
+
+    fn parse(file_path: &Path) -> Result<Self::Input> {
+        read_input(file_path)
+    }
+
+    fn part_1(map: &Self::Input) -> Result<(usize, ())> {
+        Ok((get_antinodes_double_spaced(map).len(), ()))
+    }
+
+    fn part_2(map: &Self::Input, _: ()) -> Result<usize> {
+        Ok(get_antidoes_by_line(map).len())
+    }
+}
+
+pub fn solve(file_path: &Path) -> Result<Report> {
+    solution::run::<Day>(file_path)
 }
@@ -0,0 +1,54 @@
+use crate::parse::ParseError;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+// Why a solver failed, so callers can tell a missing file from a malformed or unsolvable puzzle
+#[derive(Debug)]
+pub enum Error {
+    // The input could not be read
+    Io { path: PathBuf, source: io::Error },
+    // The input text does not follow the puzzle format
+    Parse(ParseError),
+    // The input is well formed but describes an impossible puzzle, e.g. a map without a guard
+    InvalidInput(String),
+    // The puzzle is valid but has no answer
+    NoSolution(String),
+    // The solver gave up after running for too long
+    Timeout { limit: Duration, msg: String },
+    // A bug in the solver itself
+    Internal(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io { path, source } => {
+                write!(f, "failed to read `{}`: {}", path.display(), source)
+            }
+            Error::Parse(err) => write!(f, "{}", err),
+            Error::InvalidInput(msg) => write!(f, "invalid input: {}", msg),
+            Error::NoSolution(msg) => write!(f, "no solution: {}", msg),
+            Error::Timeout { limit, msg } => write!(f, "timed out after {:?}: {}", limit, msg),
+            Error::Internal(msg) => write!(f, "internal error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Self {
+        Error::Parse(err)
+    }
+}
@@ -0,0 +1,63 @@
+// Random puzzle inputs of a given size, used to measure how the solvers scale
+
+// SplitMix64, deterministic for a given seed so measurements can be reproduced
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in `[low, high]`
+    pub fn range(&mut self, low: i64, high: i64) -> i64 {
+        assert!(low <= high, "empty range [{}, {}]", low, high);
+        let span = (high - low) as u64 + 1;
+        low + (self.next_u64() % span) as i64
+    }
+
+    pub fn below(&mut self, bound: usize) -> usize {
+        self.range(0, bound as i64 - 1) as usize
+    }
+
+    // True with probability `p`
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+// Input generator of a day, `size` is the quantity described by `size_unit`
+#[derive(Clone, Copy)]
+pub struct Generator {
+    pub generate: fn(usize, &mut Rng) -> String,
+    pub size_unit: &'static str,
+    // First size measured, the following ones grow by `growth`
+    pub min_size: usize,
+    pub growth: f64,
+}
+
+impl Generator {
+    // Geometrically increasing sizes, always growing by at least 1
+    pub fn sizes(&self) -> impl Iterator<Item = usize> + '_ {
+        std::iter::successors(Some(self.min_size.max(1)), |&size| {
+            Some(((size as f64 * self.growth).round() as usize).max(size + 1))
+        })
+    }
+}
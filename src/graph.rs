@@ -0,0 +1,471 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::hash::Hash;
+
+// Directed graph with nodes stored in insertion order and edges as adjacency lists
+#[derive(Debug, Clone)]
+pub struct Graph<N> {
+    nodes: Vec<N>,
+    indices: HashMap<N, usize>,
+    successors: Vec<Vec<usize>>,
+    edge_set: HashSet<(usize, usize)>,
+}
+
+// Nodes of a cycle in order, the last node has an edge back to the first one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<N>(pub Vec<N>);
+
+impl<N: fmt::Display> fmt::Display for Cycle<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for node in self.0.iter() {
+            write!(f, "{} -> ", node)?;
+        }
+        match self.0.first() {
+            Some(first) => write!(f, "{}", first),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<N: Clone + Eq + Hash> Default for Graph<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Clone + Eq + Hash> Graph<N> {
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            indices: HashMap::new(),
+            successors: Vec::new(),
+            edge_set: HashSet::new(),
+        }
+    }
+
+    // Adds the node if missing and returns its index
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(&index) = self.indices.get(&node) {
+            return index;
+        }
+        let index = self.nodes.len();
+        self.indices.insert(node.clone(), index);
+        self.nodes.push(node);
+        self.successors.push(Vec::new());
+        index
+    }
+
+    // Adds both nodes if missing, duplicate edges are ignored
+    pub fn add_edge(&mut self, from: N, to: N) {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+        if self.edge_set.insert((from, to)) {
+            self.successors[from].push(to);
+        }
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edge_set.len()
+    }
+
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    pub fn contains_node(&self, node: &N) -> bool {
+        self.indices.contains_key(node)
+    }
+
+    pub fn contains_edge(&self, from: &N, to: &N) -> bool {
+        match (self.indices.get(from), self.indices.get(to)) {
+            (Some(&from), Some(&to)) => self.edge_set.contains(&(from, to)),
+            _ => false,
+        }
+    }
+
+    pub fn successors(&self, node: &N) -> impl Iterator<Item = &N> {
+        self.indices
+            .get(node)
+            .into_iter()
+            .flat_map(move |&index| self.successors[index].iter().map(|&s| &self.nodes[s]))
+    }
+
+    fn in_degrees(&self) -> Vec<usize> {
+        let mut in_degrees = vec![0; self.nodes.len()];
+        for successors in self.successors.iter() {
+            for &successor in successors {
+                in_degrees[successor] += 1;
+            }
+        }
+        in_degrees
+    }
+
+    // Kahn's algorithm, ties are broken by insertion order
+    pub fn topological_sort(&self) -> Result<Vec<N>, Cycle<N>> {
+        let mut in_degrees = self.in_degrees();
+        let mut queue: VecDeque<usize> = (0..self.nodes.len())
+            .filter(|&i| in_degrees[i] == 0)
+            .collect();
+        let mut order = Vec::with_capacity(self.nodes.len());
+        while let Some(index) = queue.pop_front() {
+            order.push(self.nodes[index].clone());
+            for &successor in self.successors[index].iter() {
+                in_degrees[successor] -= 1;
+                if in_degrees[successor] == 0 {
+                    queue.push_back(successor);
+                }
+            }
+        }
+        if order.len() == self.nodes.len() {
+            return Ok(order);
+        }
+        Err(self
+            .find_cycle()
+            .expect("nodes left over by Kahn's algorithm must be part of a cycle"))
+    }
+
+    // Depth first variant, nodes come out in reverse post-order
+    pub fn topological_sort_dfs(&self) -> Result<Vec<N>, Cycle<N>> {
+        let mut post_order = Vec::with_capacity(self.nodes.len());
+        if let Some(cycle) = self.depth_first(|index| post_order.push(index)) {
+            return Err(cycle);
+        }
+        Ok(post_order
+            .into_iter()
+            .rev()
+            .map(|index| self.nodes[index].clone())
+            .collect())
+    }
+
+    pub fn find_cycle(&self) -> Option<Cycle<N>> {
+        self.depth_first(|_| {})
+    }
+
+    // Iterative depth first search over every node, stops at the first back edge
+    fn depth_first<F: FnMut(usize)>(&self, mut on_finish: F) -> Option<Cycle<N>> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum State {
+            Unvisited,
+            OnStack,
+            Done,
+        }
+        let mut states = vec![State::Unvisited; self.nodes.len()];
+        for root in 0..self.nodes.len() {
+            if states[root] != State::Unvisited {
+                continue;
+            }
+            // Pairs of node and index of the next successor to explore
+            let mut stack = vec![(root, 0)];
+            states[root] = State::OnStack;
+            while let Some(&mut (index, ref mut next)) = stack.last_mut() {
+                if let Some(&successor) = self.successors[index].get(*next) {
+                    *next += 1;
+                    match states[successor] {
+                        State::Unvisited => {
+                            states[successor] = State::OnStack;
+                            stack.push((successor, 0));
+                        }
+                        State::OnStack => {
+                            let start = stack.iter().position(|&(i, _)| i == successor)?;
+                            return Some(Cycle(
+                                stack[start..]
+                                    .iter()
+                                    .map(|&(i, _)| self.nodes[i].clone())
+                                    .collect(),
+                            ));
+                        }
+                        State::Done => {}
+                    }
+                } else {
+                    states[index] = State::Done;
+                    on_finish(index);
+                    stack.pop();
+                }
+            }
+        }
+        None
+    }
+
+    // Graph with an edge from every node to every node reachable from it
+    pub fn transitive_closure(&self) -> Graph<N> {
+        let mut closure = Graph::new();
+        for node in self.nodes.iter() {
+            closure.add_node(node.clone());
+        }
+        for start in 0..self.nodes.len() {
+            let mut seen = vec![false; self.nodes.len()];
+            let mut stack = self.successors[start].clone();
+            while let Some(index) = stack.pop() {
+                if seen[index] {
+                    continue;
+                }
+                seen[index] = true;
+                closure.add_edge(self.nodes[start].clone(), self.nodes[index].clone());
+                stack.extend(self.successors[index].iter().copied());
+            }
+        }
+        closure
+    }
+
+    // Graph made of the given nodes and the edges between them, nodes missing from `self` are added
+    pub fn induced_subgraph<'a, I>(&self, nodes: I) -> Graph<N>
+    where
+        I: IntoIterator<Item = &'a N>,
+        N: 'a,
+    {
+        let mut subgraph = Graph::new();
+        for node in nodes {
+            subgraph.add_node(node.clone());
+        }
+        for (from, successors) in self.successors.iter().enumerate() {
+            if !subgraph.contains_node(&self.nodes[from]) {
+                continue;
+            }
+            for &to in successors {
+                if subgraph.contains_node(&self.nodes[to]) {
+                    subgraph.add_edge(self.nodes[from].clone(), self.nodes[to].clone());
+                }
+            }
+        }
+        subgraph
+    }
+}
+
+// Walks back the `previous` links from `goal` to the start
+fn build_path<N: Clone + Eq + Hash>(previous: &HashMap<N, N>, goal: N) -> Vec<N> {
+    let mut path = vec![goal];
+    while let Some(node) = previous.get(path.last().unwrap()) {
+        path.push(node.clone());
+    }
+    path.reverse();
+    path
+}
+
+// Shortest path in number of steps, including `start` and the goal
+pub fn bfs<N, I, S, G>(start: N, mut successors: S, mut is_goal: G) -> Option<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    S: FnMut(&N) -> I,
+    G: FnMut(&N) -> bool,
+{
+    let mut previous: HashMap<N, N> = HashMap::new();
+    let mut seen = HashSet::from([start.clone()]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        if is_goal(&node) {
+            return Some(build_path(&previous, node));
+        }
+        for successor in successors(&node) {
+            if seen.insert(successor.clone()) {
+                previous.insert(successor.clone(), node.clone());
+                queue.push_back(successor);
+            }
+        }
+    }
+    None
+}
+
+// Number of steps from `start` to every reachable node
+pub fn bfs_distances<N, I, S>(start: N, mut successors: S) -> HashMap<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    S: FnMut(&N) -> I,
+{
+    let mut distances = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        let distance = distances[&node];
+        for successor in successors(&node) {
+            if !distances.contains_key(&successor) {
+                distances.insert(successor.clone(), distance + 1);
+                queue.push_back(successor);
+            }
+        }
+    }
+    distances
+}
+
+// Cheapest path with non-negative edge costs, returns the path and its cost
+pub fn dijkstra<N, I, S, G>(start: N, successors: S, is_goal: G) -> Option<(Vec<N>, u64)>
+where
+    N: Clone + Eq + Hash + Ord,
+    I: IntoIterator<Item = (N, u64)>,
+    S: FnMut(&N) -> I,
+    G: FnMut(&N) -> bool,
+{
+    astar(start, successors, |_| 0, is_goal)
+}
+
+// Dijkstra guided by a `heuristic` that must never overestimate the remaining cost
+pub fn astar<N, I, S, H, G>(
+    start: N,
+    mut successors: S,
+    mut heuristic: H,
+    mut is_goal: G,
+) -> Option<(Vec<N>, u64)>
+where
+    N: Clone + Eq + Hash + Ord,
+    I: IntoIterator<Item = (N, u64)>,
+    S: FnMut(&N) -> I,
+    H: FnMut(&N) -> u64,
+    G: FnMut(&N) -> bool,
+{
+    let mut costs: HashMap<N, u64> = HashMap::from([(start.clone(), 0)]);
+    let mut previous: HashMap<N, N> = HashMap::new();
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), 0, start))]);
+    while let Some(Reverse((_, cost, node))) = heap.pop() {
+        if costs.get(&node).is_some_and(|&best| cost > best) {
+            continue;
+        }
+        if is_goal(&node) {
+            return Some((build_path(&previous, node), cost));
+        }
+        for (successor, step) in successors(&node) {
+            let next_cost = cost + step;
+            if costs.get(&successor).is_some_and(|&best| best <= next_cost) {
+                continue;
+            }
+            costs.insert(successor.clone(), next_cost);
+            previous.insert(successor.clone(), node.clone());
+            heap.push(Reverse((
+                next_cost + heuristic(&successor),
+                next_cost,
+                successor,
+            )));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(u32, u32)]) -> Graph<u32> {
+        let mut graph = Graph::new();
+        for &(from, to) in edges {
+            graph.add_edge(from, to);
+        }
+        graph
+    }
+
+    // Every node of the cycle has an edge to the next one, and the last one back to the first
+    fn assert_is_cycle(graph: &Graph<u32>, cycle: &Cycle<u32>) {
+        let nodes = &cycle.0;
+        assert!(!nodes.is_empty());
+        for (i, node) in nodes.iter().enumerate() {
+            let next = &nodes[(i + 1) % nodes.len()];
+            assert!(
+                graph.contains_edge(node, next),
+                "no edge {} -> {}",
+                node,
+                next
+            );
+        }
+    }
+
+    #[test]
+    fn duplicate_edges_are_ignored() {
+        let graph = graph(&[(1, 2), (1, 2), (2, 3)]);
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(graph.successors(&1).collect::<Vec<_>>(), [&2]);
+        assert_eq!(graph.successors(&9).count(), 0);
+    }
+
+    #[test]
+    fn topological_sorts_of_a_diamond() {
+        let graph = graph(&[(1, 2), (1, 3), (2, 4), (3, 4)]);
+        assert_eq!(graph.topological_sort(), Ok(vec![1, 2, 3, 4]));
+        assert_eq!(graph.topological_sort_dfs(), Ok(vec![1, 3, 2, 4]));
+        assert_eq!(graph.find_cycle(), None);
+    }
+
+    #[test]
+    fn topological_sort_reports_the_cycle_path() {
+        let graph = graph(&[(0, 1), (1, 2), (2, 3), (3, 4), (4, 2), (4, 5)]);
+        let expected = Cycle(vec![2, 3, 4]);
+        assert_eq!(graph.topological_sort(), Err(expected.clone()));
+        assert_eq!(graph.topological_sort_dfs(), Err(expected.clone()));
+        assert_is_cycle(&graph, &expected);
+        assert_eq!(expected.to_string(), "2 -> 3 -> 4 -> 2");
+    }
+
+    #[test]
+    fn cycle_behind_an_acyclic_part() {
+        // the first roots explored are acyclic, the cycle is only reached from the last node
+        let graph = graph(&[(1, 2), (3, 2), (4, 5), (5, 6), (6, 4)]);
+        let cycle = graph.find_cycle().expect("a cycle");
+        assert_eq!(cycle, Cycle(vec![4, 5, 6]));
+        assert_is_cycle(&graph, &cycle);
+    }
+
+    #[test]
+    fn self_loop_is_a_cycle() {
+        let graph = graph(&[(1, 2), (2, 2)]);
+        assert_eq!(graph.topological_sort(), Err(Cycle(vec![2])));
+    }
+
+    #[test]
+    fn transitive_closure_and_subgraph() {
+        let graph = graph(&[(1, 2), (2, 3), (3, 4)]);
+        let closure = graph.transitive_closure();
+        assert_eq!(closure.edge_count(), 6);
+        assert!(closure.contains_edge(&1, &4));
+        assert!(!closure.contains_edge(&4, &1));
+        let subgraph = graph.induced_subgraph(&[1, 2, 4]);
+        assert_eq!(subgraph.nodes(), [1, 2, 4]);
+        assert_eq!(subgraph.edge_count(), 1);
+        assert!(subgraph.contains_edge(&1, &2));
+    }
+
+    // Moves on a 5x5 grid, `(x, y)` with a wall at `x == 2` except at `y == 4`
+    fn moves(&(x, y): &(i32, i32)) -> Vec<(i32, i32)> {
+        [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]
+            .into_iter()
+            .filter(|&(x, y)| (0..5).contains(&x) && (0..5).contains(&y))
+            .filter(|&(x, y)| x != 2 || y == 4)
+            .collect()
+    }
+
+    #[test]
+    fn bfs_goes_around_walls() {
+        let path = bfs((0, 0), moves, |&node| node == (4, 0)).expect("a path");
+        assert_eq!(path.len(), 13);
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(4, 0)));
+        assert_eq!(bfs((0, 0), moves, |&node| node == (9, 9)), None);
+        let distances = bfs_distances((0, 0), moves);
+        assert_eq!(distances[&(4, 0)], 12);
+        assert_eq!(distances.len(), 21);
+    }
+
+    #[test]
+    fn dijkstra_and_astar_prefer_cheaper_paths() {
+        // the direct edge costs more than the detour
+        let edges = |&node: &u32| -> Vec<(u32, u64)> {
+            match node {
+                0 => vec![(3, 10), (1, 2)],
+                1 => vec![(2, 2)],
+                2 => vec![(3, 2)],
+                _ => vec![],
+            }
+        };
+        assert_eq!(
+            dijkstra(0, edges, |&node| node == 3),
+            Some((vec![0, 1, 2, 3], 6))
+        );
+        assert_eq!(
+            astar(0, edges, |&node| 3 - node as u64, |&node| node == 3),
+            Some((vec![0, 1, 2, 3], 6))
+        );
+        assert_eq!(dijkstra(3, edges, |&node| node == 0), None);
+    }
+}
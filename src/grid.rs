@@ -0,0 +1,239 @@
+use crate::error::{Error, Result};
+use crate::point::{Direction, Point};
+use std::ops::{Index, IndexMut};
+
+// Dense rectangular grid stored row by row, positions are `(row, column)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    rows: usize,
+    columns: usize,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(rows: usize, columns: usize, fill: T) -> Self {
+        Self {
+            cells: vec![fill; rows * columns],
+            rows,
+            columns,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    // Parses one row per non-empty line, every row must have the same number of cells
+    pub fn parse_with<F: FnMut(char) -> Result<T>>(input: &str, mut parse_cell: F) -> Result<Self> {
+        let mut cells = Vec::new();
+        let mut rows = 0;
+        let mut columns = 0;
+        for (line_index, line) in input.lines().enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+            let row_start = cells.len();
+            for ch in line.chars() {
+                cells.push(parse_cell(ch)?);
+            }
+            let row_len = cells.len() - row_start;
+            if rows == 0 {
+                columns = row_len;
+            } else if row_len != columns {
+                return Err(Error::InvalidInput(format!(
+                    "line {} does not have the expected number of cells, expected {}, got {}",
+                    line_index + 1,
+                    columns,
+                    row_len
+                )));
+            }
+            rows += 1;
+        }
+        Ok(Self {
+            cells,
+            rows,
+            columns,
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    // Bounds check for positions computed with signed arithmetic
+    pub fn contains(&self, row: i64, column: i64) -> bool {
+        0 <= row && row < self.rows as i64 && 0 <= column && column < self.columns as i64
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> Option<&T> {
+        if row < self.rows && column < self.columns {
+            Some(&self.cells[row * self.columns + column])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, row: usize, column: usize) -> Option<&mut T> {
+        if row < self.rows && column < self.columns {
+            Some(&mut self.cells[row * self.columns + column])
+        } else {
+            None
+        }
+    }
+
+    // Every cell with its position, row by row
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let columns = self.columns;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| ((i / columns, i % columns), cell))
+    }
+
+    pub fn find_all<P: Fn(&T) -> bool>(&self, predicate: P) -> Vec<(usize, usize)> {
+        self.iter()
+            .filter(|(_, cell)| predicate(cell))
+            .map(|(position, _)| position)
+            .collect()
+    }
+
+    pub fn map<U, F: Fn(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            rows: self.rows,
+            columns: self.columns,
+        }
+    }
+
+    pub fn row(&self, row: usize) -> impl Iterator<Item = &T> {
+        self.cells[row * self.columns..(row + 1) * self.columns].iter()
+    }
+
+    pub fn column(&self, column: usize) -> impl Iterator<Item = &T> {
+        self.column_positions(column).map(move |p| &self[p])
+    }
+
+    pub fn row_positions(&self, row: usize) -> impl Iterator<Item = (usize, usize)> {
+        (0..self.columns).map(move |column| (row, column))
+    }
+
+    pub fn column_positions(&self, column: usize) -> impl Iterator<Item = (usize, usize)> {
+        (0..self.rows).map(move |row| (row, column))
+    }
+
+    // Number of diagonals (and of anti-diagonals) of the grid
+    pub fn diagonal_count(&self) -> usize {
+        (self.rows + self.columns).saturating_sub(1)
+    }
+
+    // Diagonal going from top-left to bottom-right, the first one starts at the bottom-left corner
+    pub fn diagonal_positions(&self, diagonal: usize) -> impl Iterator<Item = (usize, usize)> {
+        let columns = self.columns as i64;
+        let offset = diagonal as i64 - (self.rows as i64 - 1);
+        (0..self.rows).filter_map(move |row| {
+            let column = row as i64 + offset;
+            (0 <= column && column < columns).then_some((row, column as usize))
+        })
+    }
+
+    // Diagonal going from top-right to bottom-left, the first one starts at the top-left corner
+    pub fn anti_diagonal_positions(&self, diagonal: usize) -> impl Iterator<Item = (usize, usize)> {
+        let columns = self.columns;
+        (0..self.rows.min(diagonal + 1)).filter_map(move |row| {
+            let column = diagonal - row;
+            (column < columns).then_some((row, column))
+        })
+    }
+
+    // Position of a point within the grid, `None` when it lies outside
+    pub fn position_of(&self, point: Point) -> Option<(usize, usize)> {
+        point
+            .to_position()
+            .filter(|&(row, column)| row < self.rows && column < self.columns)
+    }
+
+    pub fn contains_point(&self, point: Point) -> bool {
+        self.position_of(point).is_some()
+    }
+
+    pub fn get_point(&self, point: Point) -> Option<&T> {
+        self.position_of(point).map(|position| &self[position])
+    }
+
+    pub fn get_point_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.position_of(point)
+            .map(move |position| &mut self[position])
+    }
+
+    fn neighbours(
+        &self,
+        row: usize,
+        column: usize,
+        directions: &'static [Direction],
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let origin = Point::from_position((row, column));
+        directions
+            .iter()
+            .filter_map(move |&direction| self.position_of(origin + direction))
+    }
+
+    // Orthogonal neighbours within the grid
+    pub fn neighbours_4(
+        &self,
+        row: usize,
+        column: usize,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbours(row, column, &Direction::ORTHOGONAL)
+    }
+
+    // Orthogonal and diagonal neighbours within the grid
+    pub fn neighbours_8(
+        &self,
+        row: usize,
+        column: usize,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbours(row, column, &Direction::ALL)
+    }
+}
+
+impl Grid<char> {
+    pub fn parse(input: &str) -> Result<Self> {
+        Self::parse_with(input, Ok)
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, column): (usize, usize)) -> &T {
+        assert!(
+            row < self.rows && column < self.columns,
+            "position ({}, {}) is outside of the {}x{} grid",
+            row,
+            column,
+            self.rows,
+            self.columns
+        );
+        &self.cells[row * self.columns + column]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, column): (usize, usize)) -> &mut T {
+        assert!(
+            row < self.rows && column < self.columns,
+            "position ({}, {}) is outside of the {}x{} grid",
+            row,
+            column,
+            self.rows,
+            self.columns
+        );
+        &mut self.cells[row * self.columns + column]
+    }
+}
@@ -0,0 +1,331 @@
+use crate::error::{Error, Result};
+use crate::point::Point;
+use crate::render::{Canvas, Color};
+use std::fs;
+use std::path::Path;
+
+type Rgb = [u8; 3];
+
+// Colours used for empty cells (`.`), other cells of the base grid and each layer colour
+#[derive(Debug, Clone, Copy)]
+pub struct Palette {
+    pub empty: Rgb,
+    pub base: Rgb,
+    // Indexed by `Color`
+    pub colors: [Rgb; 9],
+}
+
+impl Palette {
+    pub const DARK: Palette = Palette {
+        empty: [24, 24, 32],
+        base: [70, 70, 82],
+        colors: [
+            [0, 0, 0],
+            [230, 60, 60],
+            [80, 200, 100],
+            [240, 200, 60],
+            [70, 120, 230],
+            [200, 90, 210],
+            [70, 200, 220],
+            [235, 235, 235],
+            [128, 128, 128],
+        ],
+    };
+
+    pub const LIGHT: Palette = Palette {
+        empty: [250, 250, 245],
+        base: [200, 200, 195],
+        colors: [
+            [0, 0, 0],
+            [200, 30, 30],
+            [30, 150, 60],
+            [210, 160, 0],
+            [30, 80, 200],
+            [150, 40, 160],
+            [0, 150, 170],
+            [255, 255, 255],
+            [110, 110, 110],
+        ],
+    };
+
+    pub fn from_name(name: &str) -> Result<Palette> {
+        match name {
+            "dark" => Ok(Palette::DARK),
+            "light" => Ok(Palette::LIGHT),
+            _ => Err(Error::InvalidInput(format!(
+                "unknown palette `{}`, expected `dark` or `light`",
+                name
+            ))),
+        }
+    }
+
+    fn color(&self, color: Color) -> Rgb {
+        self.colors[color as usize]
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ImageOptions {
+    // Width and height in pixels of a grid cell
+    pub cell_size: usize,
+    pub palette: Palette,
+}
+
+impl Default for ImageOptions {
+    fn default() -> Self {
+        Self {
+            cell_size: 4,
+            palette: Palette::DARK,
+        }
+    }
+}
+
+// Row-major RGB pixels
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+fn blend(from: Rgb, to: Rgb, ratio: f64) -> Rgb {
+    [0, 1, 2].map(|i| (from[i] as f64 + (to[i] as f64 - from[i] as f64) * ratio).round() as u8)
+}
+
+// Colour of a cell: the last layer marking it wins, layers marking cells several times fade in like a heatmap.
+// `max_weights` holds the highest weight of every layer
+fn cell_color(
+    canvas: &Canvas,
+    position: (usize, usize),
+    palette: &Palette,
+    max_weights: &[u32],
+) -> Rgb {
+    let mut color = match canvas.base_glyph(position) {
+        '.' => palette.empty,
+        _ => palette.base,
+    };
+    let point = Point::from_position(position);
+    for (layer, &max_weight) in canvas.layers().iter().zip(max_weights) {
+        let weight = layer.weight(point);
+        let Some(layer_color) = layer.style.background.or(layer.style.foreground) else {
+            continue;
+        };
+        if weight == 0 {
+            continue;
+        }
+        let ratio = if max_weight > 1 {
+            0.35 + 0.65 * (weight - 1) as f64 / (max_weight - 1) as f64
+        } else {
+            1.0
+        };
+        color = blend(color, palette.color(layer_color), ratio);
+    }
+    color
+}
+
+impl Image {
+    pub fn from_canvas(canvas: &Canvas, options: &ImageOptions) -> Image {
+        let cell_size = options.cell_size.max(1);
+        let width = canvas.columns() * cell_size;
+        let height = canvas.rows() * cell_size;
+        let mut pixels = vec![0; width * height * 3];
+        let max_weights: Vec<u32> = canvas
+            .layers()
+            .iter()
+            .map(|layer| layer.max_weight())
+            .collect();
+        for row in 0..canvas.rows() {
+            for column in 0..canvas.columns() {
+                let color = cell_color(canvas, (row, column), &options.palette, &max_weights);
+                for y in row * cell_size..(row + 1) * cell_size {
+                    for x in column * cell_size..(column + 1) * cell_size {
+                        let offset = (y * width + x) * 3;
+                        pixels[offset..offset + 3].copy_from_slice(&color);
+                    }
+                }
+            }
+        }
+        Image {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    // Binary PPM (P6)
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut bytes = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        bytes.extend_from_slice(&self.pixels);
+        bytes
+    }
+
+    // 8-bit RGB PNG, the image data is stored without compression
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut bytes = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&(self.width as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height as u32).to_be_bytes());
+        // bit depth 8, colour type RGB, default compression, filter and interlace methods
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+        write_chunk(&mut bytes, b"IHDR", &header);
+
+        // every scanline starts with filter type 0 (none)
+        let row_len = self.width * 3;
+        let mut scanlines = Vec::with_capacity((row_len + 1) * self.height);
+        for row in self.pixels.chunks(row_len.max(1)).take(self.height) {
+            scanlines.push(0);
+            scanlines.extend_from_slice(row);
+        }
+        write_chunk(&mut bytes, b"IDAT", &zlib_stored(&scanlines));
+        write_chunk(&mut bytes, b"IEND", &[]);
+        bytes
+    }
+
+    // The format is picked from the extension of `path`, `.png` or `.ppm`
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let bytes = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("png") => self.to_png(),
+            Some("ppm") => self.to_ppm(),
+            _ => {
+                return Err(Error::InvalidInput(format!(
+                    "cannot export `{}`, the image must be a `.png` or `.ppm` file",
+                    path.display()
+                )))
+            }
+        };
+        fs::write(path, bytes).map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+fn write_chunk(bytes: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    bytes.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = bytes.len();
+    bytes.extend_from_slice(kind);
+    bytes.extend_from_slice(data);
+    let crc = crc32(&bytes[start..]);
+    bytes.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+// Zlib stream made of uncompressed deflate blocks, each holding at most 65535 bytes
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![0x78, 0x01];
+    let mut blocks = data.chunks(65535).peekable();
+    if blocks.peek().is_none() {
+        bytes.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let is_last = blocks.peek().is_none();
+        bytes.push(is_last as u8);
+        let len = block.len() as u16;
+        bytes.extend_from_slice(&len.to_le_bytes());
+        bytes.extend_from_slice(&(!len).to_le_bytes());
+        bytes.extend_from_slice(block);
+    }
+    bytes.extend_from_slice(&adler32(data).to_be_bytes());
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+    use crate::render::Style;
+
+    #[test]
+    fn crc32_matches_known_values() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        // CRC of the IEND chunk, the last 4 bytes of every PNG
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+    }
+
+    #[test]
+    fn adler32_matches_known_values() {
+        assert_eq!(adler32(b""), 1);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+        // large enough for both sums to wrap around the modulus
+        assert_eq!(adler32(&[0xff; 6000]), 0xa497_59ea);
+    }
+
+    #[test]
+    fn zlib_stored_splits_blocks() {
+        assert_eq!(
+            zlib_stored(b""),
+            [0x78, 0x01, 1, 0, 0, 0xff, 0xff, 0, 0, 0, 1]
+        );
+        let data = vec![7; 70000];
+        let bytes = zlib_stored(&data);
+        // header, two blocks of a 5 bytes header each and the checksum
+        assert_eq!(bytes.len(), 2 + 5 + 65535 + 5 + 4465 + 4);
+        assert_eq!(bytes[2..7], [0, 0xff, 0xff, 0, 0]);
+        let second = 2 + 5 + 65535;
+        assert_eq!(bytes[second..second + 5], [1, 0x71, 0x11, 0x8e, 0xee]);
+        assert_eq!(bytes[bytes.len() - 4..], adler32(&data).to_be_bytes());
+    }
+
+    #[test]
+    fn png_has_signature_header_and_end() {
+        let image = Image {
+            width: 2,
+            height: 1,
+            pixels: vec![255, 0, 0, 0, 0, 255],
+        };
+        let png = image.to_png();
+        assert_eq!(
+            png[..8],
+            [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n']
+        );
+        assert_eq!(png[8..16], [0, 0, 0, 13, b'I', b'H', b'D', b'R']);
+        assert_eq!(png[16..29], [0, 0, 0, 2, 0, 0, 0, 1, 8, 2, 0, 0, 0]);
+        assert_eq!(png[29..33], crc32(&png[12..29]).to_be_bytes());
+        assert_eq!(
+            png[png.len() - 12..],
+            [0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]
+        );
+    }
+
+    #[test]
+    fn layers_fade_in_with_their_weight() {
+        let mut canvas = Canvas::new(Grid::new(1, 3, '.'));
+        let layer = canvas.add_layer("heat", Style::fg(Color::Red));
+        layer.mark(Point::from_position((0, 0)));
+        layer.mark(Point::from_position((0, 0)));
+        layer.mark(Point::from_position((0, 0)));
+        layer.mark(Point::from_position((0, 1)));
+        let palette = Palette::DARK;
+        let image = Image::from_canvas(
+            &canvas,
+            &ImageOptions {
+                cell_size: 1,
+                palette,
+            },
+        );
+        let red = palette.color(Color::Red);
+        assert_eq!(image.pixels[0..3], red);
+        assert_eq!(image.pixels[3..6], blend(palette.empty, red, 0.35));
+        assert_eq!(image.pixels[6..9], palette.empty);
+    }
+}
@@ -0,0 +1,153 @@
+use crate::error::{Error, Result};
+use crate::parse::{Line, Source};
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+// Puzzle input mapped in memory, pages are loaded by the OS as they are read
+pub struct MappedInput {
+    path: PathBuf,
+    map: Option<Mmap>,
+}
+
+impl MappedInput {
+    pub fn open(file_path: &Path) -> Result<Self> {
+        let file = open_file(file_path)?;
+        let len = file.metadata().map_err(io_error(file_path))?.len();
+        // empty files cannot be mapped
+        let map = match len {
+            0 => None,
+            // Safety: inputs are not expected to change while a day is being solved
+            _ => Some(unsafe { Mmap::map(&file) }.map_err(io_error(file_path))?),
+        };
+        Ok(Self {
+            path: file_path.to_path_buf(),
+            map,
+        })
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        self.map.as_deref().unwrap_or_default()
+    }
+
+    pub fn text(&self) -> Result<&str> {
+        std::str::from_utf8(self.bytes()).map_err(|err| {
+            Error::InvalidInput(format!(
+                "`{}` is not valid UTF-8: {}",
+                self.path.display(),
+                err
+            ))
+        })
+    }
+}
+
+fn open_file(file_path: &Path) -> Result<File> {
+    File::open(file_path).map_err(io_error(file_path))
+}
+
+fn io_error(path: &Path) -> impl FnOnce(std::io::Error) -> Error + '_ {
+    move |source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+enum Lines {
+    Mapped { input: MappedInput, offset: usize },
+    Buffered(BufReader<File>),
+}
+
+// Reads an input one line at a time so only the current line is held in memory,
+// parsing errors still point at the right line of the file
+pub struct LineReader {
+    lines: Lines,
+    // Holds the current line only
+    source: Source,
+}
+
+impl LineReader {
+    fn new(file_path: &Path, lines: Lines) -> Self {
+        Self {
+            lines,
+            source: Source {
+                file: Some(file_path.to_path_buf()),
+                text: String::new(),
+                first_line: 0,
+            },
+        }
+    }
+
+    // Memory mapped when possible, buffered reads otherwise, e.g. for pipes
+    pub fn open(file_path: &Path) -> Result<Self> {
+        Self::mapped(file_path).or_else(|_| Self::buffered(file_path))
+    }
+
+    pub fn mapped(file_path: &Path) -> Result<Self> {
+        let input = MappedInput::open(file_path)?;
+        Ok(Self::new(file_path, Lines::Mapped { input, offset: 0 }))
+    }
+
+    pub fn buffered(file_path: &Path) -> Result<Self> {
+        let reader = BufReader::new(open_file(file_path)?);
+        Ok(Self::new(file_path, Lines::Buffered(reader)))
+    }
+
+    // Loads the next line into `source.text`, returns false at the end of the input
+    fn read_line(&mut self) -> Result<bool> {
+        let text = &mut self.source.text;
+        text.clear();
+        match &mut self.lines {
+            Lines::Mapped { input, offset } => {
+                let bytes = &input.bytes()[*offset..];
+                if bytes.is_empty() {
+                    return Ok(false);
+                }
+                let len = bytes
+                    .iter()
+                    .position(|&byte| byte == b'\n')
+                    .map_or(bytes.len(), |i| i + 1);
+                *offset += len;
+                let line = std::str::from_utf8(&bytes[..len]).map_err(|err| {
+                    Error::InvalidInput(format!(
+                        "line {} of `{}` is not valid UTF-8: {}",
+                        self.source.first_line + 1,
+                        input.path.display(),
+                        err
+                    ))
+                })?;
+                text.push_str(line);
+            }
+            Lines::Buffered(reader) => {
+                let path = self.source.file.as_deref().unwrap_or(Path::new(""));
+                if reader.read_line(text).map_err(io_error(path))? == 0 {
+                    return Ok(false);
+                }
+            }
+        }
+        // same line endings as `str::lines`
+        if text.ends_with('\n') {
+            text.pop();
+            if text.ends_with('\r') {
+                text.pop();
+            }
+        }
+        self.source.first_line += 1;
+        Ok(true)
+    }
+
+    // Next line of the input, `None` once it is exhausted
+    pub fn next_line(&mut self) -> Result<Option<Line<'_>>> {
+        Ok(self.read_line()?.then(|| self.source.as_line()))
+    }
+
+    // Next non-empty line of the input
+    pub fn next_content_line(&mut self) -> Result<Option<Line<'_>>> {
+        while self.read_line()? {
+            if !self.source.text.trim().is_empty() {
+                return Ok(Some(self.source.as_line()));
+            }
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,29 @@
+pub mod answer;
+pub mod bitgrid;
+pub mod cache;
+pub mod complexity;
+pub mod cycle;
+pub mod error;
+pub mod generate;
+pub mod graph;
+pub mod grid;
+pub mod image;
+pub mod input;
+pub mod math;
+pub mod memory;
+pub mod parse;
+pub mod point;
+pub mod render;
+pub mod report;
+pub mod search;
+pub mod simulation;
+pub mod solution;
+
+pub mod day_1;
+pub mod day_2;
+pub mod day_3;
+pub mod day_4;
+pub mod day_5;
+pub mod day_6;
+pub mod day_7;
+pub mod day_8;
@@ -1,28 +1,36 @@
-use itertools::Itertools;
-use std::collections::HashMap;
+use advent_of_code_2024::generate::Generator;
+use advent_of_code_2024::image::{Image, ImageOptions, Palette};
+use advent_of_code_2024::render::Renderer;
+use advent_of_code_2024::report::Solver;
+use advent_of_code_2024::simulation::SimulationBuilder;
+use advent_of_code_2024::{cache, day_1, day_2, day_3, day_4, day_5, day_6, day_7, day_8, memory};
+use anyhow::{anyhow, Context, Result};
+use cli::{animate, batch, bench, complexity, http, leaderboard, manifest, puzzle, serve};
+use std::collections::BTreeMap;
 use std::env;
-use std::iter::Iterator;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
-pub mod day_1;
-pub mod day_2;
-pub mod day_3;
-pub mod day_4;
-pub mod day_5;
-pub mod day_6;
-pub mod day_7;
-pub mod day_8;
+mod cli;
+
+#[global_allocator]
+static ALLOCATOR: memory::CountingAllocator = memory::CountingAllocator;
+
+struct Solution {
+    solve: Solver,
+    input: PathBuf,
+}
 
 macro_rules! create_solutions {
     ($($day:expr => $module:ident),* $(,)?) => {{
-        let solutions: HashMap<usize, Box<dyn Fn()>> = [
+        let solutions: BTreeMap<usize, Solution> = [
             $(
                 (
                     $day,
-                    Box::new(|| {
-                        $module::solve(&PathBuf::from(concat!("src/day_", $day, "/input.txt")))
-                            .expect(&format!("Failed to solve day {}", $day))
-                    }) as Box<dyn Fn()>
+                    Solution {
+                        solve: $module::solve as Solver,
+                        input: PathBuf::from(concat!("src/day_", $day, "/input.txt")),
+                    }
                 ),
             )*
         ]
@@ -32,7 +40,328 @@ macro_rules! create_solutions {
     }};
 }
 
-fn main() {
+// Days that can draw their grid with `--render`
+fn renderer(day: usize) -> Option<Renderer> {
+    match day {
+        4 => Some(day_4::render),
+        6 => Some(day_6::render),
+        8 => Some(day_8::render),
+        _ => None,
+    }
+}
+
+// Days whose simulation can be replayed with `--animate`
+fn simulation(day: usize) -> Option<SimulationBuilder> {
+    match day {
+        6 => Some(day_6::simulate),
+        _ => None,
+    }
+}
+
+// Days with an input generator to measure how they scale with `complexity`
+fn generator(day: usize) -> Option<Generator> {
+    match day {
+        1 => Some(day_1::GENERATOR),
+        2 => Some(day_2::GENERATOR),
+        3 => Some(day_3::GENERATOR),
+        4 => Some(day_4::GENERATOR),
+        5 => Some(day_5::GENERATOR),
+        6 => Some(day_6::GENERATOR),
+        7 => Some(day_7::GENERATOR),
+        8 => Some(day_8::GENERATOR),
+        _ => None,
+    }
+}
+
+fn arg_value<'a>(args: &'a [String], flag: &str) -> Option<&'a String> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1))
+}
+
+fn has_flag(args: &[String], flag: &str) -> bool {
+    args.iter().any(|arg| arg == flag)
+}
+
+fn parse_arg<T: std::str::FromStr>(args: &[String], flag: &str, default: T) -> Result<T> {
+    match arg_value(args, flag) {
+        Some(value) => value
+            .parse::<T>()
+            .map_err(|_| anyhow!("Invalid value for `{}`: `{}`", flag, value)),
+        None => Ok(default),
+    }
+}
+
+// Days selected with `--day`, or every solved day
+fn selected_days<'a>(
+    solutions: &'a BTreeMap<usize, Solution>,
+    args: &[String],
+) -> Result<Vec<(usize, &'a Solution)>> {
+    match arg_value(args, "--day") {
+        Some(day_str) => {
+            let day = day_str
+                .parse::<usize>()
+                .map_err(|_| anyhow!("Invalid day number: `{}`", day_str))?;
+            match solutions.get(&day) {
+                Some(solution) => Ok(vec![(day, solution)]),
+                None => Err(anyhow!(
+                    "Challenge for day: {} has not been solved yet :'(",
+                    day
+                )),
+            }
+        }
+        None => Ok(solutions.iter().map(|(&day, s)| (day, s)).collect()),
+    }
+}
+
+fn run_bench_compare(args: &[String]) -> Result<()> {
+    let options = bench::CompareOptions {
+        history: PathBuf::from(
+            arg_value(args, "--history").map_or(bench::DEFAULT_HISTORY_FILE, |h| h.as_str()),
+        ),
+        baseline: arg_value(args, "--baseline").cloned(),
+        current: arg_value(args, "--current").cloned(),
+        threshold: parse_arg(args, "--threshold", bench::DEFAULT_THRESHOLD)?,
+    };
+    let regressions = bench::compare(&options)?;
+    if regressions > 0 {
+        println!("{} regression(s) found", regressions);
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn run_bench(solutions: &BTreeMap<usize, Solution>, args: &[String]) -> Result<()> {
+    let samples = parse_arg(args, "--samples", bench::DEFAULT_SAMPLES)?;
+    let history = PathBuf::from(
+        arg_value(args, "--history").map_or(bench::DEFAULT_HISTORY_FILE, |h| h.as_str()),
+    );
+    for (day, solution) in selected_days(solutions, args)? {
+        let entries = bench::run(day, solution.solve, &solution.input, samples)
+            .context(format!("Failed to benchmark day {}", day))?;
+        bench::print_entries(&entries);
+        bench::append_history(&history, &entries)?;
+    }
+    Ok(())
+}
+
+fn run_batch(solutions: &BTreeMap<usize, Solution>, args: &[String]) -> Result<()> {
+    let inputs_dir = PathBuf::from(
+        arg_value(args, "--batch")
+            .filter(|dir| !dir.starts_with("--"))
+            .map_or(batch::DEFAULT_INPUTS_DIR, |dir| dir.as_str()),
+    );
+    let timeout = match arg_value(args, "--timeout") {
+        Some(seconds) => Some(
+            seconds
+                .parse::<f64>()
+                .ok()
+                .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+                .filter(|limit| !limit.is_zero())
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Invalid value for `--timeout`: `{}`, expected a positive number of seconds",
+                        seconds
+                    )
+                })?,
+        ),
+        None => None,
+    };
+    for (day, solution) in selected_days(solutions, args)? {
+        let inputs = batch::find_inputs(&inputs_dir, day)?;
+        println!("Day {}", day);
+        if inputs.is_empty() {
+            println!(
+                "no inputs found in `{}`\n",
+                inputs_dir.join(format!("day_{}", day)).display()
+            );
+            continue;
+        }
+        println!(
+            "{}",
+            batch::render_table(&batch::run(solution.solve, &inputs, timeout))
+        );
+    }
+    Ok(())
+}
+
+fn run_animation(solutions: &BTreeMap<usize, Solution>, args: &[String]) -> Result<()> {
+    let fps = animate::check_fps(parse_arg(args, "--fps", animate::DEFAULT_FPS)?)?;
+    for (day, solution) in selected_days(solutions, args)? {
+        let Some(simulate) = simulation(day) else {
+            println!("day {} has no simulation to animate", day);
+            continue;
+        };
+        let mut simulation =
+            simulate(&solution.input).context(format!("Failed to simulate day {}", day))?;
+        let animator = animate::Animator {
+            fps,
+            skip: parse_arg(args, "--skip", 1)?,
+            color: env::var_os("NO_COLOR").is_none(),
+        };
+        animator.play(simulation.as_mut())?;
+    }
+    Ok(())
+}
+
+fn run_complexity(solutions: &BTreeMap<usize, Solution>, args: &[String]) -> Result<()> {
+    let options = complexity::Options {
+        seed: parse_arg(args, "--seed", complexity::DEFAULT_SEED)?,
+        samples: parse_arg(args, "--samples", complexity::DEFAULT_SAMPLES)?,
+        max_seconds: parse_arg(args, "--max-seconds", complexity::DEFAULT_MAX_SECONDS)?,
+        max_points: parse_arg(args, "--max-points", complexity::DEFAULT_MAX_POINTS)?,
+    };
+    for (day, solution) in selected_days(solutions, args)? {
+        let Some(generator) = generator(day) else {
+            println!("day {} has no input generator", day);
+            continue;
+        };
+        complexity::run(day, solution.solve, &generator, &options)?;
+    }
+    Ok(())
+}
+
+fn run_leaderboard(args: &[String]) -> Result<()> {
+    let board = match (arg_value(args, "--url"), args.get(2)) {
+        (Some(url), _) => leaderboard::Leaderboard::from_url(url)?,
+        (None, Some(file)) => leaderboard::Leaderboard::from_file(&PathBuf::from(file))?,
+        (None, None) => {
+            return Err(anyhow!(
+                "Usage: leaderboard <file.json> or leaderboard --url <http://...>"
+            ))
+        }
+    };
+    print!("{}", board.render());
+    Ok(())
+}
+
+fn run_read(args: &[String]) -> Result<()> {
+    let day_str = arg_value(args, "--day").ok_or_else(|| {
+        anyhow!("Usage: read --day <N> [--url http://...] [--refresh] [--force] [--width 100]")
+    })?;
+    let day = day_str
+        .parse::<usize>()
+        .map_err(|_| anyhow!("Invalid day number: `{}`", day_str))?;
+    let base_url = http::base_url(arg_value(args, "--url").map(|url| url.as_str()))?;
+    let html = puzzle::fetch(day, &base_url, has_flag(args, "--refresh"))
+        .context(format!("Failed to fetch the puzzle of day {}", day))?;
+    let width = parse_arg(args, "--width", puzzle::DEFAULT_WIDTH)?;
+    print!(
+        "{}",
+        puzzle::to_text(&html, width, env::var_os("NO_COLOR").is_none())
+    );
+    let examples = puzzle::examples(&html);
+    for (path, written) in puzzle::write_examples(day, &examples, has_flag(args, "--force"))? {
+        if written {
+            println!("example written to `{}`", path.display());
+        } else {
+            println!("example `{}` already exists, kept", path.display());
+        }
+    }
+    Ok(())
+}
+
+fn run_replay(solutions: &BTreeMap<usize, Solution>, args: &[String]) -> Result<()> {
+    let manifest_path = PathBuf::from(
+        arg_value(args, "--manifest").map_or(manifest::DEFAULT_MANIFEST_FILE, |m| m.as_str()),
+    );
+    let recorded = manifest::Manifest::read(&manifest_path)?;
+    let mismatches = manifest::replay(&recorded, |day| {
+        solutions.get(&day).map(|solution| solution.solve)
+    })?;
+    if mismatches > 0 {
+        println!("{} mismatch(es) found", mismatches);
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn run_serve(args: &[String]) -> Result<()> {
+    let options = serve::Options {
+        port: parse_arg(args, "--port", serve::DEFAULT_PORT)?,
+        manifest: PathBuf::from(
+            arg_value(args, "--manifest").map_or(manifest::DEFAULT_MANIFEST_FILE, |m| m.as_str()),
+        ),
+        history: PathBuf::from(
+            arg_value(args, "--history").map_or(bench::DEFAULT_HISTORY_FILE, |h| h.as_str()),
+        ),
+    };
+    serve::run(options, renderer)
+}
+
+// Draws the day's grid in the terminal with `--render` and/or saves it with `--export-image <path>`
+fn render_day(day: usize, solution: &Solution, args: &[String], several_days: bool) -> Result<()> {
+    let Some(render) = renderer(day) else {
+        println!("day {} has no grid to render", day);
+        return Ok(());
+    };
+    let canvas = render(&solution.input).context(format!("Failed to render day {}", day))?;
+    if has_flag(args, "--render") {
+        // https://no-color.org
+        let color = env::var_os("NO_COLOR").is_none();
+        print!("{}", canvas.render(color));
+    }
+    if let Some(path) = arg_value(args, "--export-image") {
+        let mut path = PathBuf::from(path);
+        // one image per day, e.g. `grid.png` becomes `grid_day_6.png`
+        if several_days {
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+            let extension = path.extension().unwrap_or_default().to_string_lossy();
+            path = path.with_file_name(format!("{}_day_{}.{}", stem, day, extension));
+        }
+        let options = ImageOptions {
+            cell_size: parse_arg(args, "--cell-size", ImageOptions::default().cell_size)?,
+            palette: match arg_value(args, "--palette") {
+                Some(name) => Palette::from_name(name)?,
+                None => ImageOptions::default().palette,
+            },
+        };
+        Image::from_canvas(&canvas, &options).write(&path)?;
+        println!("image of day {} written to `{}`", day, path.display());
+    }
+    Ok(())
+}
+
+fn run_solutions(solutions: &BTreeMap<usize, Solution>, args: &[String]) -> Result<()> {
+    let days = selected_days(solutions, args)?;
+    let print_header = days.len() > 1;
+    let mut run_manifest = manifest::Manifest::new();
+    for (day, solution) in days {
+        if print_header {
+            println!("{}", "-".repeat(30));
+            println!("{}", "-".repeat(30));
+            println!("Solving challenge for day: {}", day);
+        }
+        let tracker = memory::Tracker::start();
+        let start = Instant::now();
+        let report =
+            (solution.solve)(&solution.input).context(format!("Failed to solve day {}", day))?;
+        let elapsed = start.elapsed();
+        let day_memory = tracker.finish();
+        report.print();
+        if let Some(memory) = day_memory {
+            println!("day {} memory (including input parsing): {}", day, memory);
+        }
+        run_manifest.add_day(day, &solution.input, &report, elapsed)?;
+        if has_flag(args, "--render") || has_flag(args, "--export-image") {
+            render_day(day, solution, args, print_header)?;
+        }
+    }
+    if has_flag(args, "--no-manifest") {
+        return Ok(());
+    }
+    let manifest_path = PathBuf::from(
+        arg_value(args, "--manifest")
+            .filter(|path| !path.starts_with("--"))
+            .map_or(manifest::DEFAULT_MANIFEST_FILE, |m| m.as_str()),
+    );
+    if manifest_path.exists() {
+        run_manifest.merge(manifest::Manifest::read(&manifest_path)?);
+    }
+    run_manifest.write(&manifest_path)
+}
+
+fn main() -> Result<()> {
     let solutions = create_solutions! {
         1 => day_1,
         2 => day_2,
@@ -45,29 +374,48 @@ fn main() {
     };
 
     let args: Vec<String> = env::args().collect();
-    match args
-        .iter()
-        .position(|arg| arg == "--day")
-        .and_then(|i| args.get(i + 1))
-    {
-        Some(day_str) => match day_str.parse::<usize>() {
-            Ok(day) => {
-                if let Some(solve) = solutions.get(&day) {
-                    solve();
-                } else {
-                    println!("Challenge for day: {} has not been solved yet :'(", day);
-                }
-            }
-            Err(_) => println!("Invalid day number: `{}`", day_str),
-        },
-        None => solutions
-            .iter()
-            .sorted_by_key(|(&k, _)| k)
-            .for_each(|(k, solve)| {
-                println!("{}", "-".repeat(30));
-                println!("{}", "-".repeat(30));
-                println!("Solving challenge for day: {}", k);
-                solve()
-            }),
+    if has_flag(&args, "--mem") {
+        memory::enable();
+    }
+    if has_flag(&args, "--cache") {
+        cache::enable(&PathBuf::from(
+            arg_value(&args, "--cache")
+                .filter(|dir| !dir.starts_with("--"))
+                .map_or(cache::DEFAULT_CACHE_DIR, |dir| dir.as_str()),
+        ));
+    }
+    if args.get(1).map(|s| s.as_str()) == Some("bench") {
+        return match args.get(2).map(|s| s.as_str()) {
+            Some("compare") => run_bench_compare(&args),
+            _ => Err(anyhow!(
+                "Usage: bench compare [--baseline <commit>] [--threshold <percent>]"
+            )),
+        };
     }
+    if args.get(1).map(|s| s.as_str()) == Some("complexity") {
+        return run_complexity(&solutions, &args);
+    }
+    if args.get(1).map(|s| s.as_str()) == Some("leaderboard") {
+        return run_leaderboard(&args);
+    }
+    if args.get(1).map(|s| s.as_str()) == Some("read") {
+        return run_read(&args);
+    }
+    if args.get(1).map(|s| s.as_str()) == Some("replay") {
+        return run_replay(&solutions, &args);
+    }
+    if args.get(1).map(|s| s.as_str()) == Some("serve") {
+        return run_serve(&args);
+    }
+    if has_flag(&args, "--animate") {
+        return run_animation(&solutions, &args);
+    }
+    if has_flag(&args, "--batch") {
+        return run_batch(&solutions, &args);
+    }
+    if has_flag(&args, "--bench") {
+        return run_bench(&solutions, &args);
+    }
+
+    run_solutions(&solutions, &args)
 }
@@ -0,0 +1,300 @@
+// Number theory helpers on `i64`, the plain variants panic on overflow and the `checked_` ones return `None`
+
+pub fn checked_gcd(a: i64, b: i64) -> Option<i64> {
+    let (mut a, mut b) = (a.checked_abs()?, b.checked_abs()?);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    Some(a)
+}
+
+// Always non-negative, `gcd(0, 0)` is 0
+pub fn gcd(a: i64, b: i64) -> i64 {
+    checked_gcd(a, b).expect("overflow in gcd")
+}
+
+pub fn checked_lcm(a: i64, b: i64) -> Option<i64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / checked_gcd(a, b)?).checked_mul(b)?.checked_abs()
+}
+
+// Always non-negative, 0 when either value is 0
+pub fn lcm(a: i64, b: i64) -> i64 {
+    checked_lcm(a, b).expect("overflow in lcm")
+}
+
+pub fn checked_gcd_all(values: &[i64]) -> Option<i64> {
+    values
+        .iter()
+        .try_fold(0, |acc, &value| checked_gcd(acc, value))
+}
+
+// 0 for an empty slice
+pub fn gcd_all(values: &[i64]) -> i64 {
+    checked_gcd_all(values).expect("overflow in gcd")
+}
+
+pub fn checked_lcm_all(values: &[i64]) -> Option<i64> {
+    values
+        .iter()
+        .try_fold(1, |acc, &value| checked_lcm(acc, value))
+}
+
+// 1 for an empty slice
+pub fn lcm_all(values: &[i64]) -> i64 {
+    checked_lcm_all(values).expect("overflow in lcm")
+}
+
+pub fn checked_extended_gcd(a: i64, b: i64) -> Option<(i64, i64, i64)> {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_s, mut s) = (1i64, 0i64);
+    let (mut old_t, mut t) = (0i64, 1i64);
+    while r != 0 {
+        let quotient = old_r.checked_div(r)?;
+        (old_r, r) = (r, old_r.checked_sub(quotient.checked_mul(r)?)?);
+        (old_s, s) = (s, old_s.checked_sub(quotient.checked_mul(s)?)?);
+        (old_t, t) = (t, old_t.checked_sub(quotient.checked_mul(t)?)?);
+    }
+    if old_r < 0 {
+        return Some((
+            old_r.checked_neg()?,
+            old_s.checked_neg()?,
+            old_t.checked_neg()?,
+        ));
+    }
+    Some((old_r, old_s, old_t))
+}
+
+// `(g, x, y)` such that `a * x + b * y = g = gcd(a, b)`
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    checked_extended_gcd(a, b).expect("overflow in extended gcd")
+}
+
+// Inverse of `a` modulo `modulus` in `[0, modulus)`, `None` when they are not coprime or on overflow
+pub fn checked_mod_inverse(a: i64, modulus: i64) -> Option<i64> {
+    if modulus <= 0 {
+        return None;
+    }
+    let (g, x, _) = checked_extended_gcd(a.rem_euclid(modulus), modulus)?;
+    (g == 1).then(|| x.rem_euclid(modulus))
+}
+
+// `None` when `a` and `modulus` are not coprime
+pub fn mod_inverse(a: i64, modulus: i64) -> Option<i64> {
+    assert!(modulus > 0, "modulus must be positive, got {}", modulus);
+    checked_mod_inverse(a, modulus)
+}
+
+// `base^exponent mod modulus` in `[0, modulus)`, intermediate products are computed on 128 bits
+pub fn checked_mod_pow(base: i64, mut exponent: u64, modulus: i64) -> Option<i64> {
+    if modulus <= 0 {
+        return None;
+    }
+    let modulus = modulus as i128;
+    let mut base = (base as i128).rem_euclid(modulus);
+    let mut result = 1 % modulus;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exponent >>= 1;
+    }
+    Some(result as i64)
+}
+
+pub fn mod_pow(base: i64, exponent: u64, modulus: i64) -> i64 {
+    checked_mod_pow(base, exponent, modulus)
+        .unwrap_or_else(|| panic!("modulus must be positive, got {}", modulus))
+}
+
+// Solves `x = residue (mod modulus)` for every pair, moduli do not need to be coprime.
+// Returns `(x, lcm of the moduli)` with `x` in `[0, lcm)`, `None` when the congruences are
+// incompatible or on overflow
+pub fn checked_crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let mut result: (i128, i128) = (0, 1);
+    for &(residue, modulus) in congruences {
+        if modulus <= 0 {
+            return None;
+        }
+        let (x, m) = result;
+        let (residue, modulus) = (
+            (residue as i128).rem_euclid(modulus as i128),
+            modulus as i128,
+        );
+        let (g, p, _) = checked_extended_gcd(m as i64, modulus as i64)?;
+        let g = g as i128;
+        if (residue - x) % g != 0 {
+            return None;
+        }
+        let step = modulus / g;
+        // m * k = residue - x (mod modulus), with k = (residue - x) / g * p (mod modulus / g)
+        let k = ((residue - x) / g % step * (p as i128 % step)).rem_euclid(step);
+        let lcm = i64::try_from(m * step).ok()? as i128;
+        result = ((x + m * k).rem_euclid(lcm), lcm);
+    }
+    Some((result.0 as i64, result.1 as i64))
+}
+
+// `None` when the congruences are incompatible
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    for &(_, modulus) in congruences {
+        assert!(modulus > 0, "modulus must be positive, got {}", modulus);
+    }
+    let lcm = congruences
+        .iter()
+        .try_fold(1i64, |acc, &(_, modulus)| checked_lcm(acc, modulus));
+    assert!(lcm.is_some(), "overflow in crt");
+    checked_crt(congruences)
+}
+
+// Floor of the square root, `None` for negative values
+pub fn checked_isqrt(n: i64) -> Option<i64> {
+    (n >= 0).then(|| n.isqrt())
+}
+
+pub fn isqrt(n: i64) -> i64 {
+    checked_isqrt(n).unwrap_or_else(|| panic!("square root of negative number {}", n))
+}
+
+// Number of decimal digits of the absolute value, 1 for 0
+pub fn digit_count(n: i64) -> u32 {
+    n.unsigned_abs().checked_ilog10().unwrap_or(0) + 1
+}
+
+// Digits of `b` written after the digits of `a`, e.g. `concat(12, 345) == 12345`, `b` must not be negative
+pub fn checked_concat(a: i64, b: i64) -> Option<i64> {
+    if b < 0 {
+        return None;
+    }
+    a.checked_mul(10i64.checked_pow(digit_count(b))?)?
+        .checked_add(if a < 0 { -b } else { b })
+}
+
+pub fn concat(a: i64, b: i64) -> i64 {
+    assert!(b >= 0, "cannot concatenate negative number {}", b);
+    checked_concat(a, b).expect("overflow in concat")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm_ignore_signs() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(-12, 18), 6);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(-4, 6), 12);
+        assert_eq!(lcm(0, 5), 0);
+        assert_eq!(gcd_all(&[]), 0);
+        assert_eq!(gcd_all(&[12, 18, -30]), 6);
+        assert_eq!(lcm_all(&[]), 1);
+        assert_eq!(lcm_all(&[2, 3, 4]), 12);
+    }
+
+    #[test]
+    fn gcd_and_lcm_overflow() {
+        assert_eq!(checked_gcd(i64::MIN, 1), None);
+        assert_eq!(checked_lcm(i64::MAX, i64::MAX - 1), None);
+        assert_eq!(checked_lcm_all(&[i64::MAX, 2]), None);
+    }
+
+    #[test]
+    fn extended_gcd_satisfies_bezout() {
+        for (a, b) in [
+            (240, 46),
+            (-240, 46),
+            (240, -46),
+            (-240, -46),
+            (0, -5),
+            (7, 0),
+        ] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a, b), "gcd of {} and {}", a, b);
+            assert_eq!(a * x + b * y, g, "coefficients of {} and {}", a, b);
+        }
+        assert_eq!(extended_gcd(0, 0), (0, 1, 0));
+        assert_eq!(checked_extended_gcd(i64::MIN, 0), None);
+    }
+
+    #[test]
+    fn mod_inverse_of_coprime_values() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        // -3 is 8 modulo 11
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(1, 1), Some(0));
+    }
+
+    #[test]
+    fn mod_inverse_of_other_values() {
+        assert_eq!(mod_inverse(2, 4), None);
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_inverse(0, 7), None);
+        assert_eq!(checked_mod_inverse(3, 0), None);
+        assert_eq!(checked_mod_inverse(3, -7), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "modulus must be positive")]
+    fn mod_inverse_rejects_non_positive_modulus() {
+        mod_inverse(3, 0);
+    }
+
+    #[test]
+    fn mod_pow_does_not_overflow() {
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(-2, 3, 5), 2);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+        assert_eq!(mod_pow(i64::MAX, 2, i64::MAX - 1), 1);
+        assert_eq!(checked_mod_pow(2, 3, 0), None);
+    }
+
+    #[test]
+    fn crt_of_coprime_moduli() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(-1, 5)]), Some((4, 5)));
+        assert_eq!(crt(&[]), Some((0, 1)));
+    }
+
+    #[test]
+    fn crt_of_non_coprime_moduli() {
+        assert_eq!(crt(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(3, 6), (3, 9), (3, 12)]), Some((3, 36)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+    }
+
+    #[test]
+    fn crt_of_large_moduli() {
+        // two primes close to 2^31, the product of the moduli is close to 2^62
+        assert_eq!(
+            crt(&[(123_456_789, 2_147_483_647), (987_654_321, 2_147_483_629)]),
+            Some((103_102_781_664_998_967, 4_611_685_975_477_714_963))
+        );
+        assert_eq!(checked_crt(&[(0, i64::MAX), (0, i64::MAX - 1)]), None);
+        assert_eq!(checked_crt(&[(1, 0)]), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow in crt")]
+    fn crt_panics_on_overflow() {
+        crt(&[(0, i64::MAX), (0, i64::MAX - 1)]);
+    }
+
+    #[test]
+    fn digits() {
+        assert_eq!(digit_count(0), 1);
+        assert_eq!(digit_count(-999), 3);
+        assert_eq!(digit_count(i64::MIN), 19);
+        assert_eq!(concat(12, 345), 12345);
+        assert_eq!(concat(-12, 3), -123);
+        assert_eq!(concat(7, 0), 70);
+        assert_eq!(checked_concat(1, -1), None);
+        assert_eq!(checked_concat(922_337_203_685_477_581, 0), None);
+        assert_eq!(isqrt(99), 9);
+        assert_eq!(checked_isqrt(-1), None);
+    }
+}
@@ -0,0 +1,137 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicIsize, AtomicUsize, Ordering};
+
+// Wraps the system allocator and counts allocations once `enable` has been called,
+// until then every call goes straight to the system allocator after a single flag check
+pub struct CountingAllocator;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+// Signed because memory allocated before counting was enabled can be freed afterwards
+static LIVE_BYTES: AtomicIsize = AtomicIsize::new(0);
+static PEAK_BYTES: AtomicIsize = AtomicIsize::new(0);
+
+fn record_alloc(size: usize) {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    ALLOCATED_BYTES.fetch_add(size, Ordering::Relaxed);
+    let live = LIVE_BYTES.fetch_add(size as isize, Ordering::Relaxed) + size as isize;
+    PEAK_BYTES.fetch_max(live, Ordering::Relaxed);
+}
+
+fn record_dealloc(size: usize) {
+    LIVE_BYTES.fetch_sub(size as isize, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() && ENABLED.load(Ordering::Relaxed) {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() && ENABLED.load(Ordering::Relaxed) {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        if ENABLED.load(Ordering::Relaxed) {
+            record_dealloc(layout.size());
+        }
+    }
+
+    // A reallocation counts as a new allocation of the new size replacing the old one
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() && ENABLED.load(Ordering::Relaxed) {
+            record_dealloc(layout.size());
+            record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MemoryStats {
+    pub allocations: usize,
+    pub allocated_bytes: usize,
+    // Highest amount of live memory above what was already live when tracking started
+    pub peak_bytes: usize,
+}
+
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.2} {}", value, UNITS[unit])
+    }
+}
+
+impl fmt::Display for MemoryStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} allocations, {} allocated, {} peak",
+            self.allocations,
+            format_bytes(self.allocated_bytes),
+            format_bytes(self.peak_bytes)
+        )
+    }
+}
+
+// Measures the allocations made between `start` and `finish`.
+// Trackers can be nested, the peak of an inner tracker is folded back into the outer one.
+pub struct Tracker {
+    allocations: usize,
+    allocated_bytes: usize,
+    live_bytes: isize,
+    outer_peak: isize,
+}
+
+impl Tracker {
+    pub fn start() -> Self {
+        let live_bytes = LIVE_BYTES.load(Ordering::Relaxed);
+        Self {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed),
+            allocated_bytes: ALLOCATED_BYTES.load(Ordering::Relaxed),
+            live_bytes,
+            outer_peak: PEAK_BYTES.swap(live_bytes, Ordering::Relaxed),
+        }
+    }
+
+    // Returns `None` when counting is not enabled
+    pub fn finish(self) -> Option<MemoryStats> {
+        let peak = PEAK_BYTES.fetch_max(self.outer_peak, Ordering::Relaxed);
+        if !is_enabled() {
+            return None;
+        }
+        Some(MemoryStats {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed) - self.allocations,
+            allocated_bytes: ALLOCATED_BYTES.load(Ordering::Relaxed) - self.allocated_bytes,
+            peak_bytes: (peak - self.live_bytes).max(0) as usize,
+        })
+    }
+}
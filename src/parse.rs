@@ -0,0 +1,365 @@
+use crate::error::{Error, Result};
+use crate::grid::Grid;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+// Parsing failure pointing at the offending text of the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub file: Option<PathBuf>,
+    // 1-based line and column, columns count characters
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+    pub source_line: String,
+    // Number of characters to underline, at least 1
+    pub span: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let location = match &self.file {
+            Some(file) => format!("{}:{}:{}", file.display(), self.line, self.column),
+            None => format!("{}:{}", self.line, self.column),
+        };
+        let gutter = " ".repeat(self.line.to_string().len());
+        writeln!(f, "{}", self.message)?;
+        writeln!(f, "{}--> {}", gutter, location)?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", self.line, self.source_line)?;
+        write!(
+            f,
+            "{} | {}{}",
+            gutter,
+            " ".repeat(self.column - 1),
+            "^".repeat(self.span.max(1))
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub type ParseResult<T> = std::result::Result<T, ParseError>;
+
+// Puzzle input along with the file it was read from, to locate parsing errors
+pub struct Source {
+    pub file: Option<PathBuf>,
+    pub text: String,
+    // Line number of the first line of `text`, above 1 when it is a fragment of a streamed input
+    pub first_line: usize,
+}
+
+impl Source {
+    pub fn read(file_path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(file_path).map_err(|source| Error::Io {
+            path: file_path.to_path_buf(),
+            source,
+        })?;
+        Ok(Self {
+            file: Some(file_path.to_path_buf()),
+            text,
+            first_line: 1,
+        })
+    }
+
+    pub fn from_text(text: &str) -> Self {
+        Self {
+            file: None,
+            text: text.to_string(),
+            first_line: 1,
+        }
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = Line<'_>> {
+        self.text.lines().enumerate().map(move |(i, text)| Line {
+            source: self,
+            number: i + self.first_line,
+            text,
+        })
+    }
+
+    // Non-empty lines
+    pub fn content_lines(&self) -> impl Iterator<Item = Line<'_>> {
+        self.lines().filter(|line| !line.text.trim().is_empty())
+    }
+
+    // Groups of consecutive non-empty lines separated by blank lines
+    pub fn sections(&self) -> Vec<Vec<Line<'_>>> {
+        let mut sections: Vec<Vec<Line>> = Vec::new();
+        let mut current: Vec<Line> = Vec::new();
+        for line in self.lines() {
+            if line.text.trim().is_empty() {
+                if !current.is_empty() {
+                    sections.push(current);
+                    current = Vec::new();
+                }
+                continue;
+            }
+            current.push(line);
+        }
+        if !current.is_empty() {
+            sections.push(current);
+        }
+        sections
+    }
+
+    // Rectangular grid with one cell per character, blank lines are skipped
+    pub fn char_grid(&self) -> ParseResult<Grid<char>> {
+        let mut width: Option<usize> = None;
+        for line in self.lines().filter(|line| !line.text.is_empty()) {
+            let line_width = line.text.chars().count();
+            match width {
+                None => width = Some(line_width),
+                Some(expected) if expected != line_width => {
+                    let column = line_width.min(expected);
+                    return Err(line.error_at_char(
+                        column,
+                        line_width.abs_diff(expected).max(1),
+                        format!(
+                            "row does not have the expected number of cells, expected {}, got {}",
+                            expected, line_width
+                        ),
+                    ));
+                }
+                Some(_) => {}
+            }
+        }
+        Grid::parse(&self.text).map_err(|err| self.error(self.first_line, 0, 1, err.to_string()))
+    }
+
+    // The whole text as a single line, for sources holding one line of a streamed input
+    pub fn as_line(&self) -> Line<'_> {
+        Line {
+            source: self,
+            number: self.first_line,
+            text: &self.text,
+        }
+    }
+
+    fn error(&self, line: usize, byte_offset: usize, span: usize, message: String) -> ParseError {
+        let source_line = self
+            .text
+            .lines()
+            .nth(line - self.first_line)
+            .unwrap_or_default();
+        let byte_offset = byte_offset.min(source_line.len());
+        ParseError {
+            file: self.file.clone(),
+            line,
+            column: source_line[..byte_offset].chars().count() + 1,
+            message,
+            source_line: source_line.to_string(),
+            span,
+        }
+    }
+}
+
+// A line of a `Source`, the helpers report errors at the position of the offending token
+#[derive(Clone, Copy)]
+pub struct Line<'a> {
+    source: &'a Source,
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    // Error pointing at `len` bytes starting at `byte_offset` of the line
+    pub fn error_at(&self, byte_offset: usize, len: usize, message: String) -> ParseError {
+        let span = self
+            .text
+            .get(byte_offset..byte_offset + len)
+            .map_or(1, |token| token.chars().count());
+        self.source.error(self.number, byte_offset, span, message)
+    }
+
+    fn error_at_char(&self, char_index: usize, span: usize, message: String) -> ParseError {
+        let byte_offset = self
+            .text
+            .char_indices()
+            .nth(char_index)
+            .map_or(self.text.len(), |(offset, _)| offset);
+        let mut error = self.source.error(self.number, byte_offset, 1, message);
+        error.span = span;
+        error
+    }
+
+    // Error pointing at a token, which must be a slice of this line
+    pub fn error_at_token(&self, token: &str, message: String) -> ParseError {
+        let offset = (token.as_ptr() as usize).saturating_sub(self.text.as_ptr() as usize);
+        self.error_at(offset.min(self.text.len()), token.len(), message)
+    }
+
+    // Error pointing at the whole line
+    pub fn error(&self, message: String) -> ParseError {
+        let start = self.text.len() - self.text.trim_start().len();
+        self.error_at(start, self.text.trim().len(), message)
+    }
+
+    pub fn parse_token<T: FromStr>(&self, token: &str) -> ParseResult<T>
+    where
+        T::Err: fmt::Display,
+    {
+        let trimmed = token.trim();
+        trimmed.parse::<T>().map_err(|err| {
+            self.error_at_token(trimmed, format!("failed to parse `{}`: {}", trimmed, err))
+        })
+    }
+
+    // Every integer of the line, an optional `-` directly in front of digits makes it negative
+    pub fn integers<T: FromStr>(&self) -> ParseResult<Vec<T>>
+    where
+        T::Err: fmt::Display,
+    {
+        let bytes = self.text.as_bytes();
+        let mut values = Vec::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            let negative = bytes[i] == b'-' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit);
+            if !(negative || bytes[i].is_ascii_digit()) {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            values.push(self.parse_token(&self.text[start..i])?);
+        }
+        Ok(values)
+    }
+
+    // Whitespace separated values
+    pub fn columns<T: FromStr>(&self) -> ParseResult<Vec<T>>
+    where
+        T::Err: fmt::Display,
+    {
+        self.text
+            .split_whitespace()
+            .map(|token| self.parse_token(token))
+            .collect()
+    }
+
+    // Exactly `count` whitespace separated values
+    pub fn exact_columns<T: FromStr>(&self, count: usize) -> ParseResult<Vec<T>>
+    where
+        T::Err: fmt::Display,
+    {
+        let values: Vec<T> = self.columns()?;
+        if values.len() != count {
+            return Err(self.error(format!("expected {} columns, got {}", count, values.len())));
+        }
+        Ok(values)
+    }
+
+    // Values separated by `separator`, e.g. `75,47,61` or `47|53`
+    pub fn separated<T: FromStr>(&self, separator: char) -> ParseResult<Vec<T>>
+    where
+        T::Err: fmt::Display,
+    {
+        self.text
+            .split(separator)
+            .map(|token| self.parse_token(token))
+            .collect()
+    }
+
+    // `key<separator> values...` where values are whitespace separated, e.g. `190: 10 19`
+    pub fn key_values<K: FromStr, V: FromStr>(&self, separator: char) -> ParseResult<(K, Vec<V>)>
+    where
+        K::Err: fmt::Display,
+        V::Err: fmt::Display,
+    {
+        let (key, values) = self
+            .text
+            .split_once(separator)
+            .ok_or_else(|| self.error(format!("invalid line format, missing `{}`", separator)))?;
+        let key = self.parse_token(key)?;
+        let values = values
+            .split_whitespace()
+            .map(|token| self.parse_token(token))
+            .collect::<ParseResult<Vec<V>>>()?;
+        Ok((key, values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(source: &Source, number: usize) -> Line<'_> {
+        source
+            .lines()
+            .find(|line| line.number == number)
+            .expect("line in source")
+    }
+
+    #[test]
+    fn error_points_at_the_token() {
+        let source = Source::from_text("1 2\n3   x4  5\n");
+        let err = line(&source, 2).columns::<u32>().unwrap_err();
+        assert_eq!((err.line, err.column, err.span), (2, 5, 2));
+        assert_eq!(
+            err.to_string(),
+            "failed to parse `x4`: invalid digit found in string\n \
+             --> 2:5\n  |\n2 | 3   x4  5\n  |     ^^"
+        );
+    }
+
+    #[test]
+    fn columns_count_characters() {
+        let source = Source::from_text("ab: é9");
+        let err = source.as_line().key_values::<String, u32>(':').unwrap_err();
+        assert_eq!((err.line, err.column, err.span), (1, 5, 2));
+        assert!(err.to_string().ends_with("1 | ab: é9\n  |     ^^"));
+    }
+
+    #[test]
+    fn location_of_a_streamed_fragment() {
+        let source = Source {
+            file: Some(PathBuf::from("input.txt")),
+            text: "7 x".to_string(),
+            first_line: 10,
+        };
+        let err = source.as_line().columns::<u8>().unwrap_err();
+        assert_eq!((err.line, err.column), (10, 3));
+        // the gutter is as wide as the line number
+        assert_eq!(
+            err.to_string(),
+            "failed to parse `x`: invalid digit found in string\n  \
+             --> input.txt:10:3\n   |\n10 | 7 x\n   |   ^"
+        );
+    }
+
+    #[test]
+    fn whole_line_errors_skip_the_indentation() {
+        let source = Source::from_text("  1 2  ");
+        let err = source.as_line().exact_columns::<u32>(3).unwrap_err();
+        assert_eq!((err.column, err.span), (3, 3));
+        assert_eq!(err.message, "expected 3 columns, got 2");
+        let err = source.as_line().key_values::<u32, u32>(':').unwrap_err();
+        assert_eq!(err.message, "invalid line format, missing `:`");
+    }
+
+    #[test]
+    fn ragged_grid_points_past_the_short_row() {
+        let source = Source::from_text("abc\nab\nabc");
+        let err = source.char_grid().unwrap_err();
+        assert_eq!((err.line, err.column, err.span), (2, 3, 1));
+        assert_eq!(err.source_line, "ab");
+    }
+
+    #[test]
+    fn integers_and_sections() {
+        let source = Source::from_text("p=-3,4 v=5-6\n\n\nx\ny\n");
+        assert_eq!(line(&source, 1).integers::<i32>(), Ok(vec![-3, 4, 5, -6]));
+        let sections: Vec<Vec<usize>> = source
+            .sections()
+            .iter()
+            .map(|section| section.iter().map(|line| line.number).collect())
+            .collect();
+        assert_eq!(sections, [vec![1], vec![4, 5]]);
+        assert_eq!(source.content_lines().count(), 3);
+    }
+}
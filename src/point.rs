@@ -0,0 +1,228 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+// Signed grid position or vector, rows grow downward and columns grow rightward
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point {
+    pub row: i64,
+    pub column: i64,
+}
+
+impl Point {
+    pub const fn new(row: i64, column: i64) -> Self {
+        Self { row, column }
+    }
+
+    pub fn from_position((row, column): (usize, usize)) -> Self {
+        Self::new(row as i64, column as i64)
+    }
+
+    // `None` when either coordinate is negative
+    pub fn to_position(self) -> Option<(usize, usize)> {
+        Some((
+            usize::try_from(self.row).ok()?,
+            usize::try_from(self.column).ok()?,
+        ))
+    }
+
+    pub fn checked_add(self, other: Point) -> Option<Point> {
+        Some(Point::new(
+            self.row.checked_add(other.row)?,
+            self.column.checked_add(other.column)?,
+        ))
+    }
+
+    pub fn checked_sub(self, other: Point) -> Option<Point> {
+        Some(Point::new(
+            self.row.checked_sub(other.row)?,
+            self.column.checked_sub(other.column)?,
+        ))
+    }
+
+    pub fn checked_mul(self, factor: i64) -> Option<Point> {
+        Some(Point::new(
+            self.row.checked_mul(factor)?,
+            self.column.checked_mul(factor)?,
+        ))
+    }
+
+    // Moves `distance` steps toward `direction`, `None` on overflow
+    pub fn checked_offset(self, direction: Direction, distance: i64) -> Option<Point> {
+        self.checked_add(direction.offset().checked_mul(distance)?)
+    }
+
+    pub fn neighbours_4(self) -> [Point; 4] {
+        Direction::ORTHOGONAL.map(|direction| self + direction)
+    }
+
+    pub fn neighbours_8(self) -> [Point; 8] {
+        Direction::ALL.map(|direction| self + direction)
+    }
+
+    pub fn manhattan_distance(self, other: Point) -> i64 {
+        (self.row - other.row).abs() + (self.column - other.column).abs()
+    }
+
+    pub fn chebyshev_distance(self, other: Point) -> i64 {
+        (self.row - other.row)
+            .abs()
+            .max((self.column - other.column).abs())
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.row + other.row, self.column + other.column)
+    }
+}
+
+impl Add<Direction> for Point {
+    type Output = Point;
+
+    fn add(self, direction: Direction) -> Point {
+        self + direction.offset()
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl AddAssign<Direction> for Point {
+    fn add_assign(&mut self, direction: Direction) {
+        *self = *self + direction;
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.row - other.row, self.column - other.column)
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, other: Point) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<i64> for Point {
+    type Output = Point;
+
+    fn mul(self, factor: i64) -> Point {
+        Point::new(self.row * factor, self.column * factor)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.row, -self.column)
+    }
+}
+
+// The 8 compass directions, ordered clockwise starting from `Up`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction {
+    pub const ORTHOGONAL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub const DIAGONAL: [Direction; 4] = [
+        Direction::UpRight,
+        Direction::DownRight,
+        Direction::DownLeft,
+        Direction::UpLeft,
+    ];
+
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::UpRight,
+        Direction::Right,
+        Direction::DownRight,
+        Direction::Down,
+        Direction::DownLeft,
+        Direction::Left,
+        Direction::UpLeft,
+    ];
+
+    pub fn offset(self) -> Point {
+        match self {
+            Direction::Up => Point::new(-1, 0),
+            Direction::UpRight => Point::new(-1, 1),
+            Direction::Right => Point::new(0, 1),
+            Direction::DownRight => Point::new(1, 1),
+            Direction::Down => Point::new(1, 0),
+            Direction::DownLeft => Point::new(1, -1),
+            Direction::Left => Point::new(0, -1),
+            Direction::UpLeft => Point::new(-1, -1),
+        }
+    }
+
+    // Rotates clockwise by `eighths` of a full turn, negative values rotate counter-clockwise
+    fn rotate(self, eighths: i64) -> Direction {
+        Direction::ALL[(self as i64 + eighths).rem_euclid(8) as usize]
+    }
+
+    // Quarter turn clockwise
+    pub fn turn_right(self) -> Direction {
+        self.rotate(2)
+    }
+
+    // Quarter turn counter-clockwise
+    pub fn turn_left(self) -> Direction {
+        self.rotate(-2)
+    }
+
+    pub fn reverse(self) -> Direction {
+        self.rotate(4)
+    }
+
+    pub fn is_orthogonal(self) -> bool {
+        (self as usize).is_multiple_of(2)
+    }
+
+    // Orthogonal direction drawn as `^`, `>`, `v` or `<`
+    pub fn from_arrow(arrow: char) -> Option<Direction> {
+        match arrow {
+            '^' => Some(Direction::Up),
+            '>' => Some(Direction::Right),
+            'v' => Some(Direction::Down),
+            '<' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    pub fn arrow(self) -> char {
+        match self {
+            Direction::Up => '^',
+            Direction::UpRight => '↗',
+            Direction::Right => '>',
+            Direction::DownRight => '↘',
+            Direction::Down => 'v',
+            Direction::DownLeft => '↙',
+            Direction::Left => '<',
+            Direction::UpLeft => '↖',
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use crate::error::Result;
+use crate::grid::Grid;
+use crate::point::Point;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+
+// The 8 standard ANSI colours plus the grey used to dim the background of a drawing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Grey,
+}
+
+impl Color {
+    fn ansi_code(self) -> u8 {
+        match self {
+            Color::Black => 0,
+            Color::Red => 1,
+            Color::Green => 2,
+            Color::Yellow => 3,
+            Color::Blue => 4,
+            Color::Magenta => 5,
+            Color::Cyan => 6,
+            Color::White => 7,
+            // bright black
+            Color::Grey => 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Style {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub bold: bool,
+}
+
+impl Style {
+    pub const PLAIN: Style = Style {
+        foreground: None,
+        background: None,
+        bold: false,
+    };
+
+    pub const fn fg(color: Color) -> Style {
+        Style {
+            foreground: Some(color),
+            background: None,
+            bold: false,
+        }
+    }
+
+    pub const fn bg(color: Color) -> Style {
+        Style {
+            foreground: None,
+            background: Some(color),
+            bold: false,
+        }
+    }
+
+    pub const fn bold(self) -> Style {
+        Style { bold: true, ..self }
+    }
+
+    // Colours set by `other` win, boldness accumulates
+    pub fn merge(self, other: Style) -> Style {
+        Style {
+            foreground: other.foreground.or(self.foreground),
+            background: other.background.or(self.background),
+            bold: self.bold || other.bold,
+        }
+    }
+
+    // SGR escape sequence selecting this style, starting from a reset
+    pub fn escape(self) -> String {
+        let mut codes = vec!["0".to_string()];
+        if self.bold {
+            codes.push("1".to_string());
+        }
+        if let Some(color) = self.foreground {
+            codes.push((30 + color.ansi_code()).to_string());
+        }
+        if let Some(color) = self.background {
+            codes.push((40 + color.ansi_code()).to_string());
+        }
+        format!("\x1b[{}m", codes.join(";"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Mark {
+    glyph: Option<char>,
+    // Number of times the cell was marked, drawn as a heatmap by image exports
+    weight: u32,
+}
+
+// Cells highlighted with the same style, optionally replacing the character drawn in them
+#[derive(Debug, Clone)]
+pub struct Layer {
+    pub name: String,
+    pub style: Style,
+    cells: HashMap<Point, Mark>,
+}
+
+impl Layer {
+    pub fn mark(&mut self, point: Point) {
+        self.cells.entry(point).or_default().weight += 1;
+    }
+
+    pub fn mark_with(&mut self, point: Point, glyph: char) {
+        let mark = self.cells.entry(point).or_default();
+        mark.glyph = Some(glyph);
+        mark.weight += 1;
+    }
+
+    // How many times the point was marked, 0 when it is not part of the layer
+    pub fn weight(&self, point: Point) -> u32 {
+        self.cells.get(&point).map_or(0, |mark| mark.weight)
+    }
+
+    pub fn max_weight(&self) -> u32 {
+        self.cells
+            .values()
+            .map(|mark| mark.weight)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn extend<I: IntoIterator<Item = Point>>(&mut self, points: I) {
+        for point in points {
+            self.mark(point);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+}
+
+// A character grid with overlay layers drawn on top of it, later layers are drawn last
+#[derive(Debug, Clone)]
+pub struct Canvas {
+    base: Grid<char>,
+    base_style: Style,
+    layers: Vec<Layer>,
+}
+
+// Signature of the `render` function of the days that can draw their grid
+pub type Renderer = fn(&Path) -> Result<Canvas>;
+
+impl Canvas {
+    pub fn new(base: Grid<char>) -> Self {
+        Self {
+            base,
+            base_style: Style::fg(Color::Grey),
+            layers: Vec::new(),
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.base.rows()
+    }
+
+    pub fn columns(&self) -> usize {
+        self.base.columns()
+    }
+
+    // Character of the cell before any layer is applied
+    pub fn base_glyph(&self, position: (usize, usize)) -> char {
+        self.base[position]
+    }
+
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    pub fn layer_mut(&mut self, name: &str) -> Option<&mut Layer> {
+        self.layers.iter_mut().find(|layer| layer.name == name)
+    }
+
+    pub fn add_layer(&mut self, name: &str, style: Style) -> &mut Layer {
+        self.layers.push(Layer {
+            name: name.to_string(),
+            style,
+            cells: HashMap::new(),
+        });
+        self.layers.last_mut().unwrap()
+    }
+
+    // Character and style of a cell once every layer is applied
+    pub fn cell(&self, position: (usize, usize)) -> (char, Style) {
+        let point = Point::from_position(position);
+        let mut glyph = self.base[position];
+        let mut style = self.base_style;
+        for layer in self.layers.iter() {
+            if let Some(mark) = layer.cells.get(&point) {
+                glyph = mark.glyph.unwrap_or(glyph);
+                style = style.merge(layer.style);
+            }
+        }
+        (glyph, style)
+    }
+
+    // The grid followed by a legend of the layers, escape sequences are only emitted with `color`
+    pub fn render(&self, color: bool) -> String {
+        let mut output = String::new();
+        for row in 0..self.rows() {
+            let mut current: Option<Style> = None;
+            for column in 0..self.columns() {
+                let (glyph, style) = self.cell((row, column));
+                if color && current != Some(style) {
+                    output.push_str(&style.escape());
+                    current = Some(style);
+                }
+                output.push(glyph);
+            }
+            if color {
+                output.push_str("\x1b[0m");
+            }
+            output.push('\n');
+        }
+        for layer in self.layers.iter() {
+            let sample = layer
+                .cells
+                .values()
+                .find_map(|mark| mark.glyph)
+                .unwrap_or('█');
+            if color {
+                write!(output, "{}{}\x1b[0m", layer.style.escape(), sample).unwrap();
+            } else {
+                output.push(sample);
+            }
+            writeln!(output, " {} ({} cells)", layer.name, layer.len()).unwrap();
+        }
+        output
+    }
+}
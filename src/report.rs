@@ -0,0 +1,84 @@
+use crate::answer::Answer;
+use crate::error::{Error, Result};
+use crate::memory::{MemoryStats, Tracker};
+use std::path::Path;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Answer and timing of a single part of a challenge
+#[derive(Debug, Clone)]
+pub struct PartResult {
+    pub part: usize,
+    pub description: String,
+    pub answer: Answer,
+    pub elapsed: Duration,
+    // Only recorded when running with `--mem`
+    pub memory: Option<MemoryStats>,
+}
+
+// Everything a day's `solve` produced, printing is left to the caller
+#[derive(Debug, Default)]
+pub struct Report {
+    pub parts: Vec<PartResult>,
+}
+
+impl Report {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Run and time the next part, the returned value is recorded as the part's answer
+    pub fn part<T: Clone + Into<Answer>, F: FnOnce() -> Result<T>>(
+        &mut self,
+        description: &str,
+        solve_part: F,
+    ) -> Result<T> {
+        let tracker = Tracker::start();
+        let start = Instant::now();
+        let answer = solve_part()?;
+        let elapsed = start.elapsed();
+        let memory = tracker.finish();
+        self.parts.push(PartResult {
+            part: self.parts.len() + 1,
+            description: description.to_string(),
+            answer: answer.clone().into(),
+            elapsed,
+            memory,
+        });
+        Ok(answer)
+    }
+
+    pub fn print(&self) {
+        for part in self.parts.iter() {
+            println!("{}: {}", part.description, part.answer);
+            if let Some(memory) = part.memory {
+                println!("  memory: {}", memory);
+            }
+        }
+    }
+}
+
+// Signature shared by every day's `solve`
+pub type Solver = fn(&Path) -> Result<Report>;
+
+// Runs `solve` on its own thread and gives up once `limit` is exceeded, the thread is then left to
+// finish in the background
+pub fn solve_with_timeout(solve: Solver, file_path: &Path, limit: Duration) -> Result<Report> {
+    let (sender, receiver) = mpsc::channel();
+    let input = file_path.to_path_buf();
+    thread::spawn(move || {
+        let _ = sender.send(solve(&input));
+    });
+    match receiver.recv_timeout(limit) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => Err(Error::Timeout {
+            limit,
+            msg: format!("solving `{}`", file_path.display()),
+        }),
+        Err(RecvTimeoutError::Disconnected) => Err(Error::Internal(format!(
+            "the solver panicked on `{}`",
+            file_path.display()
+        ))),
+    }
+}
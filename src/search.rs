@@ -0,0 +1,222 @@
+// Lazy enumeration of brute-force search spaces, nothing is materialised up front
+
+// Every sequence of `length` items picked from `choices`, in lexicographic order of the choices
+pub struct Product<'a, T> {
+    choices: &'a [T],
+    // Indices of the choices making the current sequence
+    indices: Vec<usize>,
+    // Whether `indices` holds a sequence that was not returned yet
+    fresh: bool,
+    done: bool,
+}
+
+impl<'a, T: Clone> Product<'a, T> {
+    pub fn new(choices: &'a [T], length: usize) -> Self {
+        Self {
+            choices,
+            indices: vec![0; length],
+            fresh: true,
+            done: choices.is_empty() && length > 0,
+        }
+    }
+
+    // Skips every remaining sequence starting with the first `depth` items of the last returned one
+    pub fn prune(&mut self, depth: usize) {
+        if depth == 0 {
+            self.done = true;
+            return;
+        }
+        self.increment(depth - 1);
+        self.fresh = true;
+    }
+
+    // Moves to the next choice at `position`, resetting the following ones and carrying over
+    fn increment(&mut self, position: usize) {
+        for index in self.indices[position + 1..].iter_mut() {
+            *index = 0;
+        }
+        for index in self.indices[..=position].iter_mut().rev() {
+            *index += 1;
+            if *index < self.choices.len() {
+                return;
+            }
+            *index = 0;
+        }
+        self.done = true;
+    }
+}
+
+impl<T: Clone> Iterator for Product<'_, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        if !self.fresh {
+            match self.indices.len() {
+                0 => self.done = true,
+                length => self.increment(length - 1),
+            }
+        }
+        self.fresh = false;
+        if self.done {
+            return None;
+        }
+        Some(
+            self.indices
+                .iter()
+                .map(|&i| self.choices[i].clone())
+                .collect(),
+        )
+    }
+}
+
+// Depth first search over sequences of `length` choices, carrying a state computed along the prefix.
+// `extend` gets the state of the prefix, the next choice and its depth and returns `None` to prune
+// every sequence starting with the extended prefix. Stops at the first complete sequence accepted by
+// `accept`, memory stays proportional to `length`.
+pub fn search<T, S, E, A>(
+    choices: &[T],
+    length: usize,
+    initial: S,
+    mut extend: E,
+    mut accept: A,
+) -> Option<Vec<T>>
+where
+    T: Copy,
+    E: FnMut(&S, T, usize) -> Option<S>,
+    A: FnMut(&S) -> bool,
+{
+    if length == 0 {
+        return accept(&initial).then(Vec::new);
+    }
+    // `states[d]` is the state after the first d choices of `path`,
+    // `next[d]` the index of the next choice to try at depth d
+    let mut states = vec![initial];
+    let mut next = vec![0];
+    let mut path = Vec::with_capacity(length);
+    while let Some(&choice_index) = next.last() {
+        let depth = next.len() - 1;
+        if choice_index == choices.len() {
+            next.pop();
+            states.pop();
+            path.pop();
+            continue;
+        }
+        *next.last_mut().unwrap() += 1;
+        let choice = choices[choice_index];
+        let Some(state) = extend(&states[depth], choice, depth) else {
+            continue;
+        };
+        if depth + 1 == length {
+            if accept(&state) {
+                path.push(choice);
+                return Some(path);
+            }
+            continue;
+        }
+        states.push(state);
+        next.push(0);
+        path.push(choice);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn product_in_lexicographic_order() {
+        let sequences: Vec<_> = Product::new(&['a', 'b'], 3).collect();
+        assert_eq!(sequences.len(), 8);
+        assert_eq!(sequences[0], ['a', 'a', 'a']);
+        assert_eq!(sequences[1], ['a', 'a', 'b']);
+        assert_eq!(sequences[7], ['b', 'b', 'b']);
+    }
+
+    #[test]
+    fn product_of_empty_spaces() {
+        assert_eq!(Product::new(&[1, 2], 0).collect::<Vec<_>>(), [vec![]]);
+        assert_eq!(Product::<u8>::new(&[], 0).count(), 1);
+        assert_eq!(Product::<u8>::new(&[], 2).count(), 0);
+    }
+
+    #[test]
+    fn product_prune_skips_the_prefix() {
+        let mut product = Product::new(&[0, 1, 2], 2);
+        let mut sequences = Vec::new();
+        while let Some(sequence) = product.next() {
+            if sequence[0] == 1 {
+                product.prune(1);
+            }
+            sequences.push(sequence);
+        }
+        assert_eq!(
+            sequences,
+            [[0, 0], [0, 1], [0, 2], [1, 0], [2, 0], [2, 1], [2, 2]]
+        );
+    }
+
+    #[test]
+    fn product_prune_carries_over() {
+        let mut product = Product::new(&[0, 1], 3);
+        assert_eq!(product.nth(2), Some(vec![0, 1, 0]));
+        // skips [0, 1, 1], the next prefix of length 2 is [1, 0]
+        product.prune(2);
+        assert_eq!(product.next(), Some(vec![1, 0, 0]));
+        // pruning the full sequence only skips it
+        product.prune(3);
+        assert_eq!(product.next(), Some(vec![1, 0, 1]));
+        // nothing follows the last prefix
+        product.prune(1);
+        assert_eq!(product.next(), None);
+    }
+
+    #[test]
+    fn product_prune_everything() {
+        let mut product = Product::new(&[0, 1], 2);
+        product.next();
+        product.prune(0);
+        assert_eq!(product.next(), None);
+    }
+
+    #[test]
+    fn search_finds_the_first_accepted_sequence() {
+        // sequences of 3 digits summing to 7
+        let mut extended = 0;
+        let found = search(
+            &[1, 2, 3],
+            3,
+            0,
+            |&sum, digit, _| {
+                extended += 1;
+                (sum + digit <= 7).then_some(sum + digit)
+            },
+            |&sum| sum == 7,
+        );
+        assert_eq!(found, Some(vec![1, 3, 3]));
+        // [1, 1, _] and [1, 2, _] are tried in full, then [1, 3, 1..3]
+        assert_eq!(extended, 1 + 3 + 3 + 3 + 3);
+    }
+
+    #[test]
+    fn search_prunes_prefixes() {
+        let mut depths = Vec::new();
+        let found = search(
+            &[1, 2],
+            4,
+            (),
+            |_, _, depth| {
+                depths.push(depth);
+                None
+            },
+            |_| true,
+        );
+        assert_eq!(found, None);
+        // nothing deeper than the first choice is explored
+        assert_eq!(depths, [0, 0]);
+        assert_eq!(
+            search(&[1], 0, 5, |_, _, _| None, |&s| s == 5),
+            Some(vec![])
+        );
+    }
+}
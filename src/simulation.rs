@@ -0,0 +1,18 @@
+use crate::error::Result;
+use crate::render::Canvas;
+use std::path::Path;
+
+// A step-based simulation that can be replayed frame by frame
+pub trait Simulation {
+    // Advances by one step, returns false once the simulation is over
+    fn step(&mut self) -> bool;
+
+    // Drawing of the current state
+    fn frame(&self) -> Canvas;
+
+    // One line describing the current state, e.g. the step number
+    fn status(&self) -> String;
+}
+
+// Signature of the `simulate` function of the days that can be animated
+pub type SimulationBuilder = fn(&Path) -> Result<Box<dyn Simulation>>;
@@ -0,0 +1,42 @@
+use crate::answer::Answer;
+use crate::error::{Error, Result};
+use crate::report::Report;
+use std::path::Path;
+
+// A day solved in stages: the input is parsed once and shared by both parts, and part 1 hands
+// whatever it computed along the way (`Artifacts`) to part 2 so it does not have to re-derive it
+pub trait Solution {
+    type Input;
+    // `()` when part 2 has nothing to reuse
+    type Artifacts;
+    type Part1: Clone + Into<Answer>;
+    type Part2: Clone + Into<Answer>;
+
+    const PART_1: &'static str;
+    const PART_2: &'static str;
+
+    fn parse(file_path: &Path) -> Result<Self::Input>;
+
+    fn part_1(input: &Self::Input) -> Result<(Self::Part1, Self::Artifacts)>;
+
+    fn part_2(input: &Self::Input, artifacts: Self::Artifacts) -> Result<Self::Part2>;
+}
+
+// Parses the input and runs both parts, parsing is not part of the timings of the parts
+pub fn run<S: Solution>(file_path: &Path) -> Result<Report> {
+    let mut report = Report::new();
+    let input = S::parse(file_path)?;
+    let mut artifacts = None;
+    report.part(S::PART_1, || {
+        let (answer, part_1_artifacts) = S::part_1(&input)?;
+        artifacts = Some(part_1_artifacts);
+        Ok(answer)
+    })?;
+    report.part(S::PART_2, || {
+        let artifacts = artifacts.take().ok_or_else(|| {
+            Error::Internal("part 2 ran without the artifacts of part 1".to_string())
+        })?;
+        S::part_2(&input, artifacts)
+    })?;
+    Ok(report)
+}
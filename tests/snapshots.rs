@@ -0,0 +1,109 @@
+// Golden snapshots of the answers of every day, for the examples and the real input.
+// `UPDATE_SNAPSHOTS=1 cargo test --test snapshots` accepts the current answers
+use advent_of_code_2024::report::Solver;
+use advent_of_code_2024::{day_1, day_2, day_3, day_4, day_5, day_6, day_7, day_8};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const UPDATE_ENV: &str = "UPDATE_SNAPSHOTS";
+
+fn updating() -> bool {
+    std::env::var_os(UPDATE_ENV).is_some_and(|value| value != "0")
+}
+
+// Inputs of a day: `input.txt` and the `example_N.txt` files next to the solver
+fn inputs(day: usize) -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("src/day_{}", day));
+    let mut inputs: Vec<PathBuf> = fs::read_dir(&dir)
+        .unwrap_or_else(|err| panic!("Failed to list `{}`: {}", dir.display(), err))
+        .map(|entry| entry.expect("directory entry").path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "txt"))
+        .collect();
+    inputs.sort();
+    inputs
+}
+
+// The answers without timings, a failing `solve` records its error
+fn normalised_output(solve: Solver, input: &Path) -> String {
+    match solve(input) {
+        Ok(report) => report
+            .parts
+            .iter()
+            .map(|part| {
+                format!(
+                    "part {} ({}): {}\n",
+                    part.part, part.description, part.answer
+                )
+            })
+            .collect(),
+        Err(err) => format!("error: {}\n", err),
+    }
+}
+
+fn check_day(day: usize, solve: Solver) {
+    let snapshot_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("snapshots/day_{}", day));
+    let mut failures = Vec::new();
+    let mut snapshots = Vec::new();
+    for input in inputs(day) {
+        let name = input
+            .file_stem()
+            .expect("input file name")
+            .to_string_lossy();
+        let snapshot = snapshot_dir.join(format!("{}.snap", name));
+        let actual = normalised_output(solve, &input);
+        let expected = fs::read_to_string(&snapshot).ok();
+        if expected.as_deref() != Some(actual.as_str()) {
+            if updating() {
+                fs::create_dir_all(&snapshot_dir).expect("snapshot directory");
+                fs::write(&snapshot, &actual).expect("snapshot written");
+            } else {
+                failures.push(format!(
+                    "`{}`\n--- expected\n{}--- actual\n{}",
+                    snapshot.display(),
+                    expected.as_deref().unwrap_or("(no snapshot)\n"),
+                    actual
+                ));
+            }
+        }
+        snapshots.push(snapshot);
+    }
+    // snapshots whose input is gone
+    for entry in fs::read_dir(&snapshot_dir).into_iter().flatten() {
+        let path = entry.expect("directory entry").path();
+        if snapshots.contains(&path) {
+            continue;
+        }
+        if updating() {
+            fs::remove_file(&path).expect("stale snapshot removed");
+        } else {
+            failures.push(format!("`{}` has no input", path.display()));
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "day {} does not match its snapshots, run with {}=1 to accept the changes:\n\n{}",
+        day,
+        UPDATE_ENV,
+        failures.join("\n")
+    );
+}
+
+macro_rules! snapshot_tests {
+    ($($test:ident => $day:expr, $module:ident;)*) => {$(
+        #[test]
+        fn $test() {
+            check_day($day, $module::solve);
+        }
+    )*};
+}
+
+snapshot_tests! {
+    day_1_snapshots => 1, day_1;
+    day_2_snapshots => 2, day_2;
+    day_3_snapshots => 3, day_3;
+    day_4_snapshots => 4, day_4;
+    day_5_snapshots => 5, day_5;
+    day_6_snapshots => 6, day_6;
+    day_7_snapshots => 7, day_7;
+    day_8_snapshots => 8, day_8;
+}